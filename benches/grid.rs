@@ -0,0 +1,140 @@
+use std::time::{Duration, Instant};
+
+use advent_of_code::{
+    geometry::Point,
+    grid::{ByteGrid, Grid},
+};
+
+// Compares the grid representations on a day 04 like workload:
+// parse a large letter grid, then look for XMAS in all 8 directions from every X.
+// Run with `cargo bench --bench grid`.
+
+const SIZE: usize = 1000;
+const RUNS: usize = 20;
+
+const DIRECTIONS: [Point; 8] = [
+    Point::new(1, 0),
+    Point::new(1, 1),
+    Point::new(0, 1),
+    Point::new(-1, 1),
+    Point::new(-1, 0),
+    Point::new(-1, -1),
+    Point::new(0, -1),
+    Point::new(1, -1),
+];
+
+fn main() {
+    let input = generate_input();
+
+    // every variant starts the search from the X cells only, so only the grid access differs
+    bench("Vec<Vec<char>>", &input, parse_rows, |rows| {
+        count_xmas(rows_positions_of(rows, 'X'), |point| {
+            rows.get(point.y as usize)
+                .and_then(|row| row.get(point.x as usize))
+                .map(|ch| *ch as u8)
+        })
+    });
+    bench(
+        "Grid<char>",
+        &input,
+        |input| Grid::parse(input, |ch| ch),
+        |grid| {
+            let starts = grid
+                .iter()
+                .filter(|(_, ch)| **ch == 'X')
+                .map(|(point, _)| point);
+            count_xmas(starts, |point| grid.get(point).map(|ch| *ch as u8))
+        },
+    );
+    bench("ByteGrid", &input, ByteGrid::new, |grid| {
+        count_xmas(grid.positions_of(b'X'), |point| grid.get(point))
+    });
+    // same search without points, stepping from index to index
+    bench("ByteGrid index", &input, ByteGrid::new, |grid| {
+        grid.indices_of(b'X')
+            .map(|start| {
+                DIRECTIONS
+                    .iter()
+                    .filter(|direction| {
+                        let mut index = Some(start);
+                        b"MAS".iter().all(|byte| {
+                            index = index.and_then(|index| grid.step(index, **direction));
+                            index.map(|index| grid.byte(index)) == Some(*byte)
+                        })
+                    })
+                    .count()
+            })
+            .sum()
+    });
+}
+
+/// Runs parse and search a few times and prints the fastest run of each, so outliers do not count.
+fn bench<'a, T>(
+    name: &str,
+    input: &'a str,
+    parse: impl Fn(&'a str) -> T,
+    search: impl Fn(&T) -> usize,
+) {
+    let mut parse_time = Duration::MAX;
+    let mut search_time = Duration::MAX;
+    let mut count = 0;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        let grid = parse(input);
+        parse_time = parse_time.min(start.elapsed());
+
+        let start = Instant::now();
+        count = search(&grid);
+        search_time = search_time.min(start.elapsed());
+    }
+    println!(
+        "{:<16} parse {:>10.3?}  search {:>10.3?}  ({} found)",
+        name, parse_time, search_time, count
+    );
+}
+
+fn parse_rows(input: &str) -> Vec<Vec<char>> {
+    input.lines().map(|line| line.chars().collect()).collect()
+}
+
+fn rows_positions_of(rows: &[Vec<char>], ch: char) -> impl Iterator<Item = Point> + '_ {
+    rows.iter().enumerate().flat_map(move |(y, row)| {
+        row.iter()
+            .enumerate()
+            .filter(move |(_, cell)| **cell == ch)
+            .map(move |(x, _)| Point::new_usize(x, y))
+    })
+}
+
+fn count_xmas(starts: impl Iterator<Item = Point>, get: impl Fn(Point) -> Option<u8>) -> usize {
+    starts
+        .map(|start| {
+            DIRECTIONS
+                .iter()
+                .filter(|direction| {
+                    b"XMAS"
+                        .iter()
+                        .enumerate()
+                        .all(|(index, byte)| get(start + **direction * index as i64) == Some(*byte))
+                })
+                .count()
+        })
+        .sum()
+}
+
+/// Deterministic letters, so every run searches the same grid.
+fn generate_input() -> String {
+    let mut state: u64 = 0x2024;
+    let mut input = String::with_capacity(SIZE * (SIZE + 1));
+    for _ in 0..SIZE {
+        for _ in 0..SIZE {
+            // xorshift
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            input.push(['X', 'M', 'A', 'S'][(state % 4) as usize]);
+        }
+        input.push('\n');
+    }
+    input
+}
@@ -0,0 +1,175 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Derive `FromStr` and `PuzzleParse` from a scan template, e.g.
+/// `#[parse("p={position} v={velocity}")]` for a struct with the fields position and velocity.
+/// Structs with named fields use {name} placeholders, tuple structs use {} in the order of the fields.
+/// Every field type has to implement `ScanField`.
+#[proc_macro_derive(PuzzleParse, attributes(parse))]
+pub fn derive_puzzle_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let template = template_attribute(&input)?;
+    let placeholders = placeholders(&template)?;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            Span::call_site(),
+            "PuzzleParse can only be derived for structs",
+        ));
+    };
+
+    let construct = match &data.fields {
+        Fields::Named(fields) => {
+            let names: Vec<String> = fields
+                .named
+                .iter()
+                .map(|field| field.ident.as_ref().unwrap().to_string())
+                .collect();
+            for placeholder in &placeholders {
+                match placeholder {
+                    None => {
+                        return Err(Error::new(
+                            template.span(),
+                            "structs with named fields need named placeholders like {x}",
+                        ))
+                    }
+                    Some(name) if !names.contains(name) => {
+                        return Err(Error::new(
+                            template.span(),
+                            format!("there is no field `{}`", name),
+                        ))
+                    }
+                    _ => (),
+                }
+            }
+            for name in &names {
+                let count = placeholders
+                    .iter()
+                    .filter(|placeholder| placeholder.as_ref() == Some(name))
+                    .count();
+                if count != 1 {
+                    return Err(Error::new(
+                        template.span(),
+                        format!(
+                            "field `{}` must appear exactly once in the template, found {} times",
+                            name, count
+                        ),
+                    ));
+                }
+            }
+            let fields = fields.named.iter().map(|field| {
+                let ident = field.ident.as_ref().unwrap();
+                let name = ident.to_string();
+                quote! { #ident: scanned.named(#name)? }
+            });
+            quote! { Self { #(#fields),* } }
+        }
+        Fields::Unnamed(fields) => {
+            if placeholders.iter().any(|placeholder| placeholder.is_some()) {
+                return Err(Error::new(
+                    template.span(),
+                    "tuple structs need unnamed placeholders {}",
+                ));
+            }
+            if placeholders.len() != fields.unnamed.len() {
+                return Err(Error::new(
+                    template.span(),
+                    format!(
+                        "template has {} placeholders, but the struct has {} fields",
+                        placeholders.len(),
+                        fields.unnamed.len()
+                    ),
+                ));
+            }
+            let fields = (0..fields.unnamed.len()).map(|index| quote! { scanned.get(#index)? });
+            quote! { Self(#(#fields),*) }
+        }
+        Fields::Unit => {
+            return Err(Error::new(
+                Span::call_site(),
+                "PuzzleParse cannot be derived for unit structs",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #type_generics #where_clause {
+            type Err = ::advent_of_code::scan::ScanError;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                let scanned = ::advent_of_code::scan::scan(#template, s)?;
+                ::std::result::Result::Ok(#construct)
+            }
+        }
+
+        impl #impl_generics ::advent_of_code::puzzle_parse::PuzzleParse for #name #type_generics #where_clause {}
+    })
+}
+
+fn template_attribute(input: &DeriveInput) -> Result<LitStr, Error> {
+    let attribute = input
+        .attrs
+        .iter()
+        .find(|attribute| attribute.path().is_ident("parse"))
+        .ok_or_else(|| {
+            Error::new(
+                Span::call_site(),
+                "PuzzleParse needs a template like #[parse(\"p={x},{y}\")]",
+            )
+        })?;
+    attribute.parse_args()
+}
+
+/// Names of all placeholders, None for {}. Mirrors the rules of the scan templates, so mistakes show up at compile time.
+fn placeholders(template: &LitStr) -> Result<Vec<Option<String>>, Error> {
+    let value = template.value();
+    let mut placeholders = Vec::new();
+    let mut rest = value.as_str();
+    // two placeholders without text between them cannot be split
+    let mut last_was_placeholder = false;
+    while let Some(ch) = rest.chars().next() {
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            rest = &rest[2..];
+            last_was_placeholder = false;
+            continue;
+        }
+        match ch {
+            '{' => {
+                let end = rest
+                    .find('}')
+                    .ok_or_else(|| Error::new(template.span(), "unclosed placeholder"))?;
+                if last_was_placeholder {
+                    return Err(Error::new(
+                        template.span(),
+                        "two placeholders without text between them",
+                    ));
+                }
+                let name = &rest[1..end];
+                placeholders.push((!name.is_empty()).then(|| name.to_string()));
+                rest = &rest[end + 1..];
+                last_was_placeholder = true;
+            }
+            '}' => {
+                return Err(Error::new(
+                    template.span(),
+                    "unopened placeholder, use }} for a literal brace",
+                ))
+            }
+            _ => {
+                rest = &rest[ch.len_utf8()..];
+                last_was_placeholder = false;
+            }
+        }
+    }
+    Ok(placeholders)
+}
@@ -1,3 +1,5 @@
+use crate::runner;
+
 pub mod aoc_2024_day_01;
 pub mod aoc_2024_day_02;
 pub mod aoc_2024_day_03;
@@ -15,5 +17,28 @@ pub mod aoc_2024_day_14;
 
 // To run way faster in release compile mode: "cargo run --release"
 pub(crate) fn run_all() {
-    aoc_2024_day_14::run();
+    // every day goes through the runner, so overflows are reported instead of ending the run
+    let days: [(&str, fn()); 14] = [
+        ("2024 day 01", aoc_2024_day_01::run),
+        ("2024 day 02", aoc_2024_day_02::run),
+        ("2024 day 03", aoc_2024_day_03::run),
+        ("2024 day 04", aoc_2024_day_04::run),
+        ("2024 day 05", aoc_2024_day_05::run),
+        ("2024 day 06", aoc_2024_day_06::run),
+        ("2024 day 07", aoc_2024_day_07::run),
+        ("2024 day 08", aoc_2024_day_08::run),
+        ("2024 day 09", aoc_2024_day_09::run),
+        ("2024 day 10", aoc_2024_day_10::run),
+        ("2024 day 11", aoc_2024_day_11::run),
+        ("2024 day 12", aoc_2024_day_12::run),
+        ("2024 day 13", aoc_2024_day_13::run),
+        ("2024 day 14", aoc_2024_day_14::run),
+    ];
+    let failed = days
+        .iter()
+        .filter(|(name, run)| runner::report(name, run).is_err())
+        .count();
+    if failed > 0 {
+        eprintln!("{} of {} days failed", failed, days.len());
+    }
 }
@@ -29,8 +29,7 @@ pub fn run() {
 }
 
 fn process_1(input: &str) -> i64 {
-    let parsed_input: Vec<Vec<&str>> = input
-        .split("\n")
+    let parsed_input: Vec<Vec<&str>> = input::lines(input)
         .map(|line| line.split(" ").collect())
         .collect();
 
@@ -55,8 +54,7 @@ fn process_1(input: &str) -> i64 {
 }
 
 fn process_2(input: &str) -> i64 {
-    let parsed_input: Vec<Vec<&str>> = input
-        .split("\n")
+    let parsed_input: Vec<Vec<&str>> = input::lines(input)
         .map(|line| line.split(" ").collect())
         .collect();
 
@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
 
-use crate::input;
+use crate::{extract::signed, input};
 
 #[test]
 pub fn test_all() {
@@ -32,13 +32,8 @@ pub fn run() {
 }
 
 fn process_1(input: &str) -> usize {
-    let parsed_input: Vec<Vec<i64>> = input
-        .split("\n")
-        .map(|line| {
-            line.split_whitespace()
-                .flat_map(|column| column.parse().ok())
-                .collect()
-        })
+    let parsed_input: Vec<Vec<i64>> = input::lines(input)
+        .map(|line| signed(line).collect())
         .filter(|num_line: &Vec<i64>| !num_line.is_empty())
         .collect();
 
@@ -72,13 +67,8 @@ fn process_1(input: &str) -> usize {
 }
 
 fn process_2(input: &str) -> usize {
-    let parsed_input: Vec<Vec<i64>> = input
-        .split("\n")
-        .map(|line| {
-            line.split_whitespace()
-                .flat_map(|column| column.parse().ok())
-                .collect()
-        })
+    let parsed_input: Vec<Vec<i64>> = input::lines(input)
+        .map(|line| signed(line).collect())
         .filter(|num_line: &Vec<i64>| !num_line.is_empty())
         .collect();
 
@@ -1,10 +1,23 @@
-use crate::input;
+use crate::{input, lexer::Lexer};
 
 #[test]
 pub fn test_all() {
     run();
 }
 
+#[test]
+pub fn test_lexer() {
+    // operands have 1 to 3 digits, longer numbers are no instruction at all
+    assert_eq!(process_1("mul(123,4)mul(1234,5)mul(6,7890)mul(7,8)"), 548);
+    // only the complete don't() disables, a bare don't is ignored
+    let kinds: Vec<Kind> = lexer()
+        .scan("don'tmul(2,3)don't()mul(4,5)do()")
+        .map(|instruction| instruction.kind)
+        .collect();
+    assert_eq!(kinds, vec![Kind::Mul, Kind::Dont, Kind::Mul, Kind::Do]);
+    assert_eq!(process_2("don'tmul(2,3)don't()mul(4,5)do()mul(1,1)"), 7);
+}
+
 pub fn run() {
     let sample_input = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
 
@@ -25,110 +38,42 @@ pub fn run() {
     assert_eq!(result, 104083373);
 }
 
-fn process_1(input: &str) -> i64 {
-    let mut state = 0;
-
-    let mut number: i64 = 0;
-
-    let mut mult1 = 0;
-
-    let mut result = 0;
-
-    for c in input.chars() {
-        match c {
-            'm' if state == 0 => state += 1,
-            'u' if state == 1 => state += 1,
-            'l' if state == 2 => state += 1,
-            '(' if state == 3 => state += 1,
-            '0'..='9' if state == 4 => {
-                number = (number * 10) + c.to_digit(10).unwrap_or(0) as i64;
-            }
-            ',' if state == 4 => {
-                mult1 = number;
-                number = 0;
-                state += 1;
-            }
-            '0'..='9' if state == 5 => {
-                number = (number * 10) + c.to_digit(10).unwrap_or(0) as i64;
-            }
-            ')' if state == 5 => {
-                result += mult1 * number;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Mul,
+    Do,
+    Dont,
+}
 
-                state = 0;
-                number = 0;
-                mult1 = 0;
-            }
-            _ => {
-                state = 0;
-                number = 0;
-                mult1 = 0;
-            }
-        }
-    }
+fn lexer() -> Lexer<Kind> {
+    // the lexer retries at every offset and prefers the longest match,
+    // so neither "mmul(" nor "do" inside of "don't()" need any special handling
+    Lexer::new()
+        .pattern(Kind::Mul, "mul({int:3},{int:3})")
+        .pattern(Kind::Do, "do()")
+        .pattern(Kind::Dont, "don't()")
+}
 
-    result
+fn process_1(input: &str) -> i64 {
+    lexer()
+        .scan(input)
+        .filter(|instruction| instruction.kind == Kind::Mul)
+        .map(|instruction| instruction.int(0) * instruction.int(1))
+        .sum()
 }
 
 fn process_2(input: &str) -> i64 {
-    let mut state = 0;
-
-    let mut number: i64 = 0;
-
-    let mut mult1 = 0;
-
-    let mut result = 0;
+    let lexer = lexer();
 
     let mut enabled = true;
-
-    for c in input.chars() {
-        match c {
-            'm' => state = 1,
-            'u' if state == 1 => state += 1,
-            'l' if state == 2 => state += 1,
-            '(' if state == 3 => state += 1,
-            '0'..='9' if state == 4 => {
-                number = (number * 10) + c.to_digit(10).unwrap_or(0) as i64;
-            }
-            ',' if state == 4 => {
-                mult1 = number;
-                number = 0;
-                state += 1;
-            }
-            '0'..='9' if state == 5 => {
-                number = (number * 10) + c.to_digit(10).unwrap_or(0) as i64;
-            }
-            ')' if state == 5 => {
-                if enabled {
-                    result += mult1 * number;
-                }
-
-                state = 0;
-                number = 0;
-                mult1 = 0;
-            }
-
-            // There are no overlapping elements in mul(x,y) and do() / don't(). So we can simply offset the state, to not have to implement a complex lexer.
-            'd' => state = 100,
-            'o' if state == 100 => state = 101,
-            '(' if state == 101 => state = 102,
-            ')' if state == 102 => {
-                enabled = true;
-                state = 0;
-            }
-
-            'n' if state == 101 => state = 110,
-            '\'' if state == 110 => state = 111,
-            't' if state == 111 => {
-                enabled = false;
-                state = 0;
-            }
-            _ => {
-                state = 0;
-                number = 0;
-                mult1 = 0;
-            }
+    let mut result = 0;
+    for instruction in lexer.scan(input) {
+        match instruction.kind {
+            Kind::Mul if enabled => result += instruction.int(0) * instruction.int(1),
+            Kind::Mul => (),
+            Kind::Do => enabled = true,
+            Kind::Dont => enabled = false,
         }
     }
-
     result
 }
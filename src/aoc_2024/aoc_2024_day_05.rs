@@ -1,6 +1,4 @@
-use std::{cmp::Ordering, collections::HashMap};
-
-use crate::input;
+use crate::{input, ordering::Rules, puzzle_parse::PuzzleParse};
 
 #[test]
 pub fn test_all() {
@@ -53,141 +51,60 @@ pub fn run() {
 }
 
 fn process_1(input: &str) -> i64 {
-    let split: Vec<&str> = input.split("\n\n").collect();
+    let (rules, updates) = parse(input);
 
-    let rules = *split.first().unwrap_or(&"");
-    let prints = *split.last().unwrap_or(&"");
+    updates
+        .iter()
+        .filter(|pages| rules.violations(pages).is_empty())
+        .map(|pages| middle_page(pages))
+        .sum()
+}
 
-    // not sure if ruleset always creates a linear sortable line of values, so use more flexible value mapping.
-
-    // key must come before value
-    let mut before_map: HashMap<&str, Vec<&str>> = HashMap::new();
-    // key must come afer value
-    let mut after_map: HashMap<&str, Vec<&str>> = HashMap::new();
-
-    rules.split("\n").for_each(|line| {
-        let split: Vec<&str> = line.split("|").collect();
-
-        let before = *split.first().unwrap_or(&"");
-        let after = *split.last().unwrap_or(&"");
-
-        before_map.entry(before).or_default().push(after);
-        after_map.entry(after).or_default().push(before);
-    });
-
-    let sum: i64 = prints
-        .split("\n")
-        .map(|line| {
-            let pages: Vec<&str> = line.split(",").collect();
-
-            let valid = pages.windows(2).all(|window| {
-                let before = window[0];
-                let after = window[1];
-                // here could be && or || depending on the implicit rules.
-                // If the rules are purely linear and every rule has it's opposite also listed, use &&
-                // If rules can be circular or might not be fully complete, use ||
-                // If rules contradict, were focked either way.
-                // -> Tested both, same result. So rules are linear and complete. So use && just because.
-                before_map
-                    .get(before)
-                    .map(|values| values.contains(&after))
-                    .unwrap_or(false)
-                    && after_map
-                        .get(after)
-                        .map(|values| values.contains(&before))
-                        .unwrap_or(false)
-            });
-
-            if valid {
-                return pages
-                    .get(pages.len() / 2)
-                    .and_then(|page| page.parse().ok())
-                    .unwrap_or(0);
-            }
-            0
+fn process_2(input: &str) -> i64 {
+    let (rules, updates) = parse(input);
+
+    updates
+        .iter()
+        .filter(|pages| !rules.violations(pages).is_empty())
+        .map(|pages| {
+            // the rules as a whole may be cyclic, only the rules between the pages of one update have to be sortable
+            let sorted = rules
+                .sort(pages)
+                .unwrap_or_else(|error| panic!("{}", error));
+            middle_page(&sorted)
         })
-        .sum();
+        .sum()
+}
 
-    sum
+#[derive(Debug, PartialEq, PuzzleParse)]
+#[parse("{before}|{after}")]
+struct Rule {
+    before: u32,
+    after: u32,
 }
 
-fn process_2(input: &str) -> i64 {
-    let split: Vec<&str> = input.split("\n\n").collect();
+#[derive(Debug, PartialEq, PuzzleParse)]
+#[parse("{pages}")]
+struct Update {
+    pages: Vec<u32>,
+}
+
+fn parse(input: &str) -> (Rules<u32>, Vec<Vec<u32>>) {
+    let split: Vec<&str> = input::sections(input).collect();
 
     let rules = *split.first().unwrap_or(&"");
     let prints = *split.last().unwrap_or(&"");
 
-    // not sure if ruleset always creates a linear sortable line of values, so use more flexible value mapping.
-
-    // key must come before value
-    let mut before_map: HashMap<&str, Vec<&str>> = HashMap::new();
-    // key must come afer value
-    let mut after_map: HashMap<&str, Vec<&str>> = HashMap::new();
-
-    rules.split("\n").for_each(|line| {
-        let split: Vec<&str> = line.split("|").collect();
-
-        let before = *split.first().unwrap_or(&"");
-        let after = *split.last().unwrap_or(&"");
-
-        before_map.entry(before).or_default().push(after);
-        after_map.entry(after).or_default().push(before);
-    });
-
-    let sum: i64 = prints
-        .split("\n")
-        .map(|line| {
-            let pages: Vec<&str> = line.split(",").collect();
-
-            let valid = pages.windows(2).all(|window| {
-                let before = window[0];
-                let after = window[1];
-                // here could be && or || depending on the implicit rules.
-                // If the rules are purely linear and every rule has it's opposite also listed, use &&
-                // If rules can be circular or might not be fully complete, use ||
-                // If rules contradict, were focked either way.
-                // -> Tested both, same result. So rules are linear and complete. So use && just because.
-                before_map
-                    .get(before)
-                    .map(|values| values.contains(&after))
-                    .unwrap_or(false)
-                    && after_map
-                        .get(after)
-                        .map(|values| values.contains(&before))
-                        .unwrap_or(false)
-            });
-
-            if !valid {
-                // reorder here
-
-                let mut sorted = pages.to_vec();
-                sorted.sort_by(|left, right| {
-                    if left == right {
-                        return Ordering::Equal;
-                    }
-                    // again, if rules are complete, we can use &&. left is &self, so if it fits, it is lesser. Otherwise greater.
-                    if before_map
-                        .get(left)
-                        .map(|values| values.contains(right))
-                        .unwrap_or(false)
-                        || after_map
-                            .get(right)
-                            .map(|values| values.contains(left))
-                            .unwrap_or(false)
-                    {
-                        return Ordering::Less;
-                    }
-                    Ordering::Greater
-                });
-
-                return sorted
-                    .get(sorted.len() / 2)
-                    .and_then(|page| page.parse().ok())
-                    .unwrap_or(0);
-            }
-            0
-        })
-        .sum();
+    let rules = Rule::parse_lines(rules).unwrap_or_else(|error| panic!("Invalid rule, {}", error));
+    let rules = Rules::from_pairs(rules.into_iter().map(|rule| (rule.before, rule.after)));
+    let updates = Update::parse_lines(prints)
+        .unwrap_or_else(|error| panic!("Invalid update, {}", error))
+        .into_iter()
+        .map(|update| update.pages)
+        .collect();
+    (rules, updates)
+}
 
-    sum
+fn middle_page(pages: &[u32]) -> i64 {
+    pages.get(pages.len() / 2).map_or(0, |page| *page as i64)
 }
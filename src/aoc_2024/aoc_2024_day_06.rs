@@ -1,17 +1,32 @@
-use std::{
-    collections::HashMap,
-    fmt::{Debug, Display},
-};
+use std::fmt::{Debug, Display};
 
-use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
-use crate::input;
+use crate::{
+    geometry::{Direction, Point},
+    grid::{BitGrid, BitGrid4, ByteGrid, History},
+    input,
+};
 
 #[test]
 pub fn test_all() {
     run();
 }
 
+#[test]
+pub fn test_record_walk() {
+    let input = ".#..
+....
+.^..";
+    let history = record_walk(&Map::parse(input));
+    // up, turn right at the obstruction and out of the map on the right
+    assert_eq!(history.step_count(), 4);
+    assert_eq!(history.frame(2).to_string(), ".#..\n.X^.\n.X..\n");
+    assert_eq!(history.grid().to_string(), ".#..\n.XXX\n.X..\n");
+    let visited = history.grid().iter().filter(|(_, ch)| **ch == 'X').count();
+    assert_eq!(visited as i64, process_1(input));
+}
+
 pub fn run() {
     let sample_input = "....#.....
 .........#
@@ -39,9 +54,12 @@ pub fn run() {
     assert_eq!(result, 1928);
 }
 
-#[derive(Debug, Clone)]
-struct Map {
-    fields: Vec<Vec<char>>,
+#[derive(Debug, Clone, Copy)]
+struct Map<'a> {
+    // the input itself, nothing is copied
+    fields: ByteGrid<'a>,
+    // an additional obstruction, instead of changing a copy of the map
+    obstacle: Option<Point>,
 }
 #[derive(Debug, Clone, PartialEq)]
 struct Position {
@@ -49,110 +67,53 @@ struct Position {
     y: i64,
     direction: Direction,
 }
-#[derive(Debug, Clone, PartialEq)]
-enum Direction {
-    Left,
-    Right,
-    Up,
-    Down,
-}
-impl Map {
-    fn parse(input: &str) -> Map {
-        let fields: Vec<Vec<char>> = input
-            .split("\n")
-            .map(|line| line.chars().collect())
-            .collect();
-        Map { fields }
+impl<'a> Map<'a> {
+    fn parse(input: &'a str) -> Map<'a> {
+        Map {
+            fields: ByteGrid::new(input),
+            obstacle: None,
+        }
     }
 
     fn get_guard(&self) -> Position {
-        for (y, line) in self.fields.iter().enumerate() {
-            for (x, field) in line.iter().enumerate() {
-                if field == &'^' {
-                    return Position {
-                        x: x as i64,
-                        y: y as i64,
-                        direction: Direction::Up,
-                    };
-                }
-            }
-        }
-
-        Position::new(0, 0, Direction::Up)
+        self.fields
+            .find(b'^')
+            .map(|point| Position::new(point.x, point.y, Direction::Up))
+            .unwrap_or(Position::new(0, 0, Direction::Up))
     }
 
     fn is_free(&self, x: i64, y: i64) -> bool {
-        if self.is_out_of_bounds(x, y) {
-            // oob is allowed and expected
-            return true;
+        if self.obstacle == Some(Point::new(x, y)) {
+            return false;
         }
-
-        let x = x as usize;
-        let y = y as usize;
         self.fields
-            .get(y)
-            .and_then(|line| line.get(x))
-            .map(|c| c != &'#')
-            .unwrap_or(false)
+            .get(Point::new(x, y))
+            .map(|c| c != b'#')
+            // oob is allowed and expected
+            .unwrap_or(true)
     }
 
-    fn is_out_of_bounds(&self, x: i64, y: i64) -> bool {
-        if y < 0
-            || y >= self.fields.len() as i64
-            || x < 0
-            || x >= self.fields[y as usize].len() as i64
-        {
-            // out of bounds is allowed and expected
-            return true;
-        }
-        false
+    fn width(&self) -> usize {
+        self.fields.width()
     }
 
-    fn clear_guard(&mut self, position: &Position) {
-        let x = position.x;
-        let y = position.y;
-
-        if self.is_out_of_bounds(x, y) {
-            return;
-        }
-
-        let x = x as usize;
-        let y = y as usize;
-
-        if let Some(line) = self.fields.get_mut(y) {
-            line[x] = 'X';
-        };
+    fn height(&self) -> usize {
+        self.fields.height()
     }
 
-    fn set_guard(&mut self, position: &Position) {
-        let x = position.x;
-        let y = position.y;
-
-        if self.is_out_of_bounds(x, y) {
-            return;
-        }
-
-        let x = x as usize;
-        let y = y as usize;
-
-        if let Some(line) = self.fields.get_mut(y) {
-            line[x] = '^';
-        };
+    fn is_out_of_bounds(&self, x: i64, y: i64) -> bool {
+        // out of bounds is allowed and expected
+        !self.fields.contains(Point::new(x, y))
     }
 }
 
-impl Display for Map {
+impl Display for Map<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::new();
-
-        for line in self.fields.iter() {
-            for c in line.iter() {
-                s.push(*c);
-            }
-            s.push('\n');
+        let mut fields = self.fields.to_grid(|byte| byte as char);
+        if let Some(obstacle) = self.obstacle {
+            fields.set(obstacle, 'O');
         }
-        s.push('\n');
-        f.write_str(s.as_str())
+        writeln!(f, "{}", fields)
     }
 }
 
@@ -161,7 +122,7 @@ impl Position {
         Position { x, y, direction }
     }
 
-    fn move_step(&mut self, map: &mut Map) {
+    fn move_step(&mut self, map: &Map) {
         let (mut new_x, mut new_y) = self.coord_in_direction();
 
         while !map.is_free(new_x, new_y) {
@@ -169,34 +130,21 @@ impl Position {
             (new_x, new_y) = self.coord_in_direction();
         }
 
-        map.clear_guard(self);
-
         self.x = new_x;
         self.y = new_y;
-
-        map.set_guard(self);
     }
 
     fn coord_in_direction(&self) -> (i64, i64) {
-        let mut new_x = self.x;
-        let mut new_y = self.y;
-
-        match self.direction {
-            Direction::Left => new_x = self.x - 1,
-            Direction::Right => new_x = self.x + 1,
-            Direction::Up => new_y = self.y - 1,
-            Direction::Down => new_y = self.y + 1,
-        }
-        (new_x, new_y)
+        let new_point = self.point() + self.direction.step();
+        (new_point.x, new_point.y)
+    }
+
+    fn point(&self) -> Point {
+        Point::new(self.x, self.y)
     }
 
     fn turn_right(&mut self) {
-        match self.direction {
-            Direction::Left => self.direction = Direction::Up,
-            Direction::Right => self.direction = Direction::Down,
-            Direction::Up => self.direction = Direction::Right,
-            Direction::Down => self.direction = Direction::Left,
-        }
+        self.direction = self.direction.turn_right();
     }
 
     fn is_out(&self, map: &Map) -> bool {
@@ -205,17 +153,32 @@ impl Position {
 }
 
 fn process_1(input: &str) -> i64 {
-    let mut map = Map::parse(input);
+    let map = Map::parse(input);
     let mut guard = map.get_guard();
 
+    let mut visited = BitGrid::new(map.width(), map.height());
     while !guard.is_out(&map) {
-        guard.move_step(&mut map);
+        visited.set(guard.point());
+
+        guard.move_step(&map);
     }
 
-    map.fields
-        .iter()
-        .map(|line| line.iter().filter(|c| c == &&'X').count() as i64)
-        .sum()
+    visited.count() as i64
+}
+
+/// for debugging, the walk of the guard step by step, the map itself stays untouched
+fn record_walk(map: &Map) -> History<char> {
+    let mut guard = map.get_guard();
+    let mut history = History::new(map.fields.to_grid(|byte| byte as char));
+    while !guard.is_out(map) {
+        history.set(guard.point(), 'X');
+
+        guard.move_step(map);
+
+        history.set(guard.point(), '^');
+        history.commit();
+    }
+    history
 }
 
 fn process_2(input: &str) -> i64 {
@@ -224,15 +187,12 @@ fn process_2(input: &str) -> i64 {
 
     let mut loop_count = 0;
 
-    let loop_count: usize = map
-        .fields
-        .par_iter()
-        .enumerate()
-        .map(|(y, line)| {
-            let sum: usize = line
-                .par_iter()
-                .enumerate()
-                .map(|(x, c)| check_map(&map, &original_guard, y, x))
+    let loop_count: usize = (0..map.height())
+        .into_par_iter()
+        .map(|y| {
+            let sum: usize = (0..map.width())
+                .into_par_iter()
+                .map(|x| check_map(&map, &original_guard, y, x))
                 .sum();
             sum
         })
@@ -243,51 +203,34 @@ fn process_2(input: &str) -> i64 {
 }
 
 fn check_map(map: &Map, original_guard: &Position, y: usize, x: usize) -> usize {
-    // clone map and guard to allow parallel cehcking
-    let mut map = map.clone();
+    // the map is only a view of the input, so it is cheap to copy for parallel checking
     let mut guard = original_guard.clone();
 
-    let old_c = map.fields[y][x];
-
-    if old_c == '^' {
+    let point = Point::new_usize(x, y);
+    if map.fields[point] == b'^' {
         return 0;
     }
 
-    map.fields[y][x] = '#';
+    let map = Map {
+        obstacle: Some(point),
+        ..*map
+    };
 
     let mut loop_detected = false;
-    // a bit more complex history map than a simple vec.
-    let mut history: HashMap<usize, HashMap<usize, Vec<Position>>> = HashMap::new();
+    // remember every direction the guard had on each field
+    let mut history = BitGrid4::new(map.width(), map.height());
     while !guard.is_out(&map) {
-        let guard_x = guard.x as usize;
-        let guard_y = guard.y as usize;
-
-        history
-            .entry(guard_y)
-            .or_default()
-            .entry(guard_x)
-            .or_default()
-            .push(guard.clone());
-
-        guard.move_step(&mut map);
-
-        let guard_x = guard.x as usize;
-        let guard_y = guard.y as usize;
-
-        if history
-            .get(&guard_y)
-            .and_then(|map| map.get(&guard_x))
-            .map(|vec| vec.contains(&guard))
-            .unwrap_or(false)
-        {
+        history.set(guard.point(), guard.direction);
+
+        guard.move_step(&map);
+
+        if history.get(guard.point(), guard.direction) {
             // loop detected
             loop_detected = true;
             break;
         }
     }
 
-    map.fields[y][x] = old_c;
-
     if loop_detected {
         return 1;
     }
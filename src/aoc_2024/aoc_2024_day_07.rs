@@ -1,6 +1,11 @@
 use rayon::iter::{ParallelBridge, ParallelIterator};
 
-use crate::input;
+use crate::{
+    arith::add,
+    equation::{is_solvable, Add, Concat, Mul, Operator},
+    extract::unsigned,
+    input,
+};
 
 #[test]
 pub fn test_all() {
@@ -36,168 +41,65 @@ pub fn run() {
 #[test]
 pub fn test_operator_generator() {
     // 5 values, two operators
-    let mut operator_cg = OperatorCombinationGenerator::new(vec![0; 5], 2);
-
-    let count = operator_cg.inspect(|ops| println!("{:?}", ops)).count();
-    assert_eq!(count, 2_usize.pow(5));
+    let operators = crate::combinatorics::cartesian_power(2, 5);
+    assert_eq!(operators.count(), 2_usize.pow(5));
 
     // 4 values, 3 operators
-    let mut operator_cg = OperatorCombinationGenerator::new(vec![0; 4], 3);
-
-    let count = operator_cg.inspect(|ops| println!("{:?}", ops)).count();
-    assert_eq!(count, 3_usize.pow(4));
+    let operators = crate::combinatorics::cartesian_power(3, 4);
+    assert_eq!(operators.count(), 3_usize.pow(4));
 }
 
-fn process_1(input: &str) -> i64 {
-    let result: i64 = input
-        .split("\n")
-        .par_bridge()
-        .map(|line| {
-            let line_split: Vec<&str> = line.split_whitespace().collect();
-
-            if line_split.len() < 2 {
-                return 0;
-            }
-
-            let expected_result: i64 = line_split
-                .first()
-                .and_then(|s| s[0..s.len() - 1].parse().ok())
-                .unwrap_or(0);
-
-            let values: Vec<i64> = line_split[1..]
-                .iter()
-                .flat_map(|s| s.parse().ok())
-                .collect();
-
-            // just for visualization
-            let operators = ["+", "*"];
-
-            let found_combinations =
-                count_operator_combinations(values, operators.len(), expected_result);
-
-            if found_combinations > 0 {
-                return expected_result;
-            }
-            0
-        })
-        .sum();
-    result
+#[test]
+pub fn test_backward_search() {
+    // the backward search has to count the same operator choices as trying all of them forward
+    let input = "3267: 81 40 27
+7290: 6 8 6 15
+192: 17 8 14
+292: 11 6 16 20
+0: 5 0
+0: 7 4294967296 4294967296 0";
+    let operators: [&dyn Operator; 3] = [&Add, &Mul, &Concat];
+    input::lines(input)
+        .flat_map(parse_line)
+        .for_each(|(target, values)| {
+            let forward = crate::combinatorics::cartesian_power(operators.len(), values.len() - 1)
+                .filter(|selection| {
+                    let selected: Vec<&dyn Operator> =
+                        selection.iter().map(|index| operators[*index]).collect();
+                    crate::equation::evaluate(&values, &selected) == Some(target)
+                })
+                .count() as u64;
+            let backward = crate::equation::solve(target, &values, &operators)
+                .map_or(0, |solutions| solutions.count);
+            assert_eq!(backward, forward);
+        });
 }
 
-fn process_2(input: &str) -> i64 {
-    let result: i64 = input
-        .split("\n")
-        .par_bridge()
-        .map(|line| {
-            let line_split: Vec<&str> = line.split_whitespace().collect();
-
-            if line_split.len() < 2 {
-                return 0;
-            }
-
-            let expected_result: i64 = line_split
-                .first()
-                .and_then(|s| s[0..s.len() - 1].parse().ok())
-                .unwrap_or(0);
-
-            let values: Vec<i64> = line_split[1..]
-                .iter()
-                .flat_map(|s| s.parse().ok())
-                .collect();
-
-            // just for visualization
-            let operators = ["+", "*", "||"];
-
-            let found_combinations =
-                count_operator_combinations(values, operators.len(), expected_result);
-
-            if found_combinations > 0 {
-                return expected_result;
-            }
-            0
-        })
-        .sum();
-    result
+fn process_1(input: &str) -> u64 {
+    sum_solvable(input, &[&Add, &Mul])
 }
 
-struct OperatorCombinationGenerator {
-    operator_selection: Vec<usize>,
-    operator_count: usize,
-    current_position: usize,
-}
-impl OperatorCombinationGenerator {
-    fn new(operator_selection: Vec<usize>, operator_count: usize) -> OperatorCombinationGenerator {
-        OperatorCombinationGenerator {
-            operator_selection,
-            operator_count,
-            current_position: 0,
-        }
-    }
+fn process_2(input: &str) -> u64 {
+    sum_solvable(input, &[&Add, &Mul, &Concat])
 }
-impl Iterator for OperatorCombinationGenerator {
-    type Item = Vec<usize>;
 
-    fn next(&mut self) -> Option<Vec<usize>> {
-        if self.current_position == 0 {
-            // Special case for first element, return initial value without incresing anything, to also get [0, 0, 0, ...].
-            self.current_position += 1;
-            return Some(self.operator_selection.clone());
-        }
-
-        let base = self.operator_count;
-        let mut add_next = 1;
-        self.operator_selection
-            .iter_mut()
-            .enumerate()
-            .for_each(|(i, sel_value)| {
-                *sel_value += add_next;
-                add_next = 0;
-                if *sel_value >= base {
-                    add_next = 1;
-                    *sel_value = 0;
-                }
-            });
-        if add_next > 0 {
-            // We overlowed the operator selection vector, so we checked every possible combination. Stop searching.
-            self.current_position += 1;
-            return None;
-        }
-        // Don't care abot complex lending iterators or stuff, just clone the vector, it isn't that big.
-        self.current_position += 1;
-        Some(self.operator_selection.clone())
-    }
+/// Sum of the test values of all lines, that can be solved with the operators.
+fn sum_solvable(input: &str, operators: &[&dyn Operator]) -> u64 {
+    input::lines(input)
+        .par_bridge()
+        .flat_map(parse_line)
+        .filter(|(expected_result, values)| is_solvable(*expected_result, values, operators))
+        .map(|(expected_result, _)| expected_result)
+        .reduce(|| 0, add)
 }
 
-fn count_operator_combinations(
-    values: Vec<i64>,
-    operator_count: usize,
-    expected_result: i64,
-) -> i32 {
-    let mut operator_cg = OperatorCombinationGenerator::new(vec![0; values.len()], operator_count);
-
-    let mut search = true;
-    let found_combinations = operator_cg
-        .map(|operator_selection| {
-            // check if current combination of operator selectors leads to ecpected result.
-            let mut sum = 0;
-            values.iter().enumerate().for_each(|(i, value)| {
-                let op_index = operator_selection[i];
-
-                match op_index {
-                    0 => sum += value,
-                    1 => sum *= value,
-                    // 12 || 345 = 12345, shift left value in base 10 to the left and add right value
-                    2 => sum = sum * 10_i64.pow(value.to_string().len() as u32) + value,
-                    _ => (),
-                }
-            });
-
-            if sum == expected_result {
-                // count all combinations that lead to expected result
-                return 1;
-            }
-            0
-        })
-        .sum();
-    found_combinations
+/// "3267: 81 40 27" into the test value and the values.
+fn parse_line(line: &str) -> Option<(u64, Vec<u64>)> {
+    let mut numbers = unsigned(line);
+    let expected_result = numbers.next()?;
+    let values: Vec<u64> = numbers.collect();
+    if values.is_empty() {
+        return None;
+    }
+    Some((expected_result, values))
 }
@@ -1,6 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::input;
+use crate::{
+    geometry::{harmonic, Bounds, Line, Point},
+    grid::ByteGrid,
+    input,
+};
 
 #[test]
 pub fn test_all() {
@@ -36,215 +40,55 @@ pub fn run() {
     assert_eq!(result, 839);
 }
 
-#[derive(Debug, Clone, PartialEq)]
-struct Element {
-    x: i64,
-    y: i64,
-    ch: char,
-}
-
 fn process_1(input: &str) -> i64 {
-    let fields: Vec<Vec<Element>> = input
-        .split("\n")
-        .enumerate()
-        .map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(x, ch)| Element {
-                    x: x as i64,
-                    y: y as i64,
-                    ch,
-                })
-                .collect()
-        })
-        .filter(|v: &Vec<Element>| !v.is_empty())
-        .collect();
-
-    let y_limit = fields.len() as i64;
-    let x_limit = fields[0].len() as i64;
-
-    let mut ch_map: HashMap<char, Vec<&Element>> = HashMap::new();
-
-    let mut updated_fields = fields.clone();
-
-    fields.iter().for_each(|line| {
-        line.iter().for_each(|element| {
-            if element.ch != '.' {
-                ch_map.entry(element.ch).or_default().push(element);
-            }
-        })
-    });
-    // fields.iter().for_each(|line| {
-    //     line.iter().for_each(|element| print!("{}", element.ch));
-    //     println!();
-    // });
-
-    let mut cross_elements: Vec<Element> = Vec::new();
-
-    ch_map.iter().for_each(|(ch, elements)| {
-        (0..elements.len()).for_each(|i| {
-            let current_element = elements[i];
-            ((i + 1)..elements.len()).for_each(|j| {
-                let mirror_element = elements[j];
-                let delta_x = mirror_element.x - current_element.x;
-                let delta_y = mirror_element.y - current_element.y;
-                let new_x_1 = current_element.x - delta_x;
-                let new_y_1 = current_element.y - delta_y;
-                let new_x_2 = mirror_element.x + delta_x;
-                let new_y_2 = mirror_element.y + delta_y;
-                if new_x_1 >= 0 && new_x_1 < x_limit && new_y_1 >= 0 && new_y_1 < y_limit {
-                    let new_element = Element {
-                        x: new_x_1,
-                        y: new_y_1,
-                        ch: '#',
-                    };
-                    if !cross_elements.contains(&new_element) {
-                        cross_elements.push(new_element.clone());
-                        updated_fields[new_y_1 as usize][new_x_1 as usize] = new_element.clone();
-                    }
-                }
-                if new_x_2 >= 0 && new_x_2 < x_limit && new_y_2 >= 0 && new_y_2 < y_limit {
-                    let new_element = Element {
-                        x: new_x_2,
-                        y: new_y_2,
-                        ch: '#',
-                    };
-                    if !cross_elements.contains(&new_element) {
-                        cross_elements.push(new_element.clone());
-                        updated_fields[new_y_2 as usize][new_x_2 as usize] = new_element.clone();
-                    }
-                }
-            });
+    let (bounds, ch_map) = parse_antennas(input);
+
+    let mut cross_points: HashSet<Point> = HashSet::new();
+
+    ch_map.iter().for_each(|(ch, points)| {
+        for_each_pair(points, |a, b| {
+            // the antinodes are mirrored on both sides of the antenna pair
+            [harmonic(a, b, -1), harmonic(a, b, 2)]
+                .into_iter()
+                .filter(|point| bounds.contains(point))
+                .for_each(|point| {
+                    cross_points.insert(point);
+                });
         });
     });
-    // updated_fields.iter().for_each(|line| {
-    //     line.iter().for_each(|element| print!("{}", element.ch));
-    //     println!();
-    // });
 
-    cross_elements.len() as i64
+    cross_points.len() as i64
 }
 
 fn process_2(input: &str) -> i64 {
-    let fields: Vec<Vec<Element>> = input
-        .split("\n")
-        .enumerate()
-        .map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(x, ch)| Element {
-                    x: x as i64,
-                    y: y as i64,
-                    ch,
-                })
-                .collect()
-        })
-        .filter(|v: &Vec<Element>| !v.is_empty())
-        .collect();
-
-    let y_limit = fields.len() as i64;
-    let x_limit = fields[0].len() as i64;
-
-    let mut ch_map: HashMap<char, Vec<&Element>> = HashMap::new();
-
-    let mut updated_fields = fields.clone();
-
-    fields.iter().for_each(|line| {
-        line.iter().for_each(|element| {
-            if element.ch != '.' {
-                ch_map.entry(element.ch).or_default().push(element);
-            }
-        })
-    });
-    // fields.iter().for_each(|line| {
-    //     line.iter().for_each(|element| print!("{}", element.ch));
-    //     println!();
-    // });
-
-    let mut cross_elements: Vec<Element> = Vec::new();
-
-    ch_map.iter().for_each(|(ch, elements)| {
-        (0..elements.len()).for_each(|i| {
-            let current_element = elements[i];
-            ((i + 1)..elements.len()).for_each(|j| {
-                let mirror_element = elements[j];
-                let delta_x = mirror_element.x - current_element.x;
-                let delta_y = mirror_element.y - current_element.y;
-
-                let mut new_x = current_element.x;
-
-                let new_element = Element {
-                    x: new_x,
-                    y: current_element.y,
-                    ch: '#',
-                };
-                if !cross_elements.contains(&new_element) {
-                    cross_elements.push(new_element.clone());
-                    updated_fields[current_element.y as usize][new_x as usize] =
-                        new_element.clone();
-                }
-
-                while new_x >= 0 && new_x < x_limit - 1 {
-                    new_x += 1;
-                    let gradient_dividend = (new_x - current_element.x) * delta_y;
-
-                    if gradient_dividend % delta_x != 0 {
-                        // not on a direct line, because gradient is no integer division
-                        continue;
-                    }
-
-                    let new_y = current_element.y + gradient_dividend / delta_x;
-
-                    if new_y < 0 || new_y >= y_limit {
-                        // y out of bounds
-                        continue;
-                    }
-
-                    let new_element = Element {
-                        x: new_x,
-                        y: new_y,
-                        ch: '#',
-                    };
-                    if !cross_elements.contains(&new_element) {
-                        cross_elements.push(new_element.clone());
-                        updated_fields[new_y as usize][new_x as usize] = new_element.clone();
-                    }
-                }
-                let mut new_x = current_element.x;
-
-                while new_x > 0 && new_x < x_limit {
-                    new_x -= 1;
-                    let gradient_dividend = (new_x - current_element.x) * delta_y;
-
-                    if gradient_dividend % delta_x != 0 {
-                        // not on a direct line, because gradient is no integer division
-                        continue;
-                    }
-
-                    let new_y = current_element.y + gradient_dividend / delta_x;
-
-                    if new_y < 0 || new_y >= y_limit {
-                        // y out of bounds
-                        continue;
-                    }
-
-                    let new_element = Element {
-                        x: new_x,
-                        y: new_y,
-                        ch: '#',
-                    };
-                    if !cross_elements.contains(&new_element) {
-                        cross_elements.push(new_element.clone());
-                        updated_fields[new_y as usize][new_x as usize] = new_element.clone();
-                    }
-                }
-            });
+    let (bounds, ch_map) = parse_antennas(input);
+
+    let mut cross_points: HashSet<Point> = HashSet::new();
+
+    ch_map.iter().for_each(|(ch, points)| {
+        for_each_pair(points, |a, b| {
+            // every lattice point on the line through both antennas is an antinode, including the antennas
+            cross_points.extend(Line::through(a, b).points_within(&bounds));
         });
     });
-    // updated_fields.iter().for_each(|line| {
-    //     line.iter().for_each(|element| print!("{}", element.ch));
-    //     println!();
-    // });
 
-    cross_elements.len() as i64
+    cross_points.len() as i64
+}
+
+fn parse_antennas(input: &str) -> (Bounds, HashMap<char, Vec<Point>>) {
+    let fields = ByteGrid::new(input);
+
+    let mut ch_map: HashMap<char, Vec<Point>> = HashMap::new();
+    fields
+        .iter()
+        .filter(|(_, byte)| *byte != b'.')
+        .for_each(|(point, byte)| ch_map.entry(byte as char).or_default().push(point));
+
+    (fields.bounds(), ch_map)
+}
+
+fn for_each_pair(points: &[Point], mut f: impl FnMut(Point, Point)) {
+    (0..points.len()).for_each(|i| {
+        ((i + 1)..points.len()).for_each(|j| f(points[i], points[j]));
+    });
 }
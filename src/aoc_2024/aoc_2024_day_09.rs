@@ -1,4 +1,6 @@
-use crate::input;
+use std::ops::Range;
+
+use crate::{input, range_set::RangeSet};
 
 #[test]
 pub fn test_all() {
@@ -81,103 +83,73 @@ fn process_1(input: &str) -> u64 {
 
 #[derive(Debug, Clone, PartialEq)]
 struct Block {
-    start: u64,
-    size: u64,
+    range: Range<u64>,
     id: u64,
-    is_used: bool,
 }
 
 fn process_2(input: &str) -> u64 {
-    let mut last_block_end = 0;
+    let mut files: Vec<Block> = Vec::new();
+    let mut free_space = RangeSet::new();
 
-    let mut blocks: Vec<Block> = input
-        .chars()
-        .enumerate()
-        .filter_map(|(index, ch)| {
-            // 0 and every even index defines a block, every uneven index defines empty space.
-            let is_used = index % 2 == 0;
+    let mut last_block_end = 0;
+    input.chars().enumerate().for_each(|(index, ch)| {
+        // The character digit of the block or the free space defines the size.
+        let size: u64 = ch.to_digit(10).unwrap_or(0) as u64;
+        let range = last_block_end..last_block_end + size;
+        last_block_end += size;
+
+        // 0 and every even index defines a block, every uneven index defines empty space.
+        if index % 2 != 0 {
+            free_space.insert(range);
+        } else if size > 0 {
             // The index of the block in the "compressed" array is the id.
-            let id: u64 = if is_used { index as u64 / 2 } else { 0 };
-
-            // The character digit of the block or the free space defines the size.
-            let size: u64 = ch.to_digit(10).unwrap_or(0) as u64;
-            if size == 0 {
-                // Blocks without size are no blocks
-                return None;
-            }
-            let start = last_block_end;
-            last_block_end += size;
-            Some(Block {
-                start,
-                size,
-                id,
-                is_used,
-            })
-        })
-        .collect();
+            files.push(Block {
+                range,
+                id: index as u64 / 2,
+            });
+        }
+    });
 
-    let mut right_index = blocks.len() - 1;
-    'a: while right_index > 0 {
-        let mut right_block = blocks[right_index].clone();
-
-        if right_block.is_used {
-            let mut left_index = 0;
-            // only move up to right index
-            while left_index < right_index {
-                let mut left_block = blocks[left_index].clone();
-
-                if !left_block.is_used && left_block.size >= right_block.size {
-                    blocks[right_index].is_used = false;
-                    blocks[right_index].id = 0;
-
-                    right_block.start = left_block.start;
-                    left_block.size -= right_block.size;
-                    left_block.start = right_block.start + right_block.size;
-
-                    if left_block.size > 0 {
-                        // still some free space left, keep original free space block
-                        blocks.insert(left_index, right_block);
-                        blocks[left_index + 1] = left_block;
-                        // we insert a new block, so go one to the right again
-                        right_index += 1;
-                    } else {
-                        // drop previous free space block
-                        blocks[left_index] = right_block;
-                    }
-                    // some position has been found, continue with next block
-                    continue 'a;
-                }
-                left_index += 1;
-            }
+    // every file is moved once, starting with the highest id
+    files.iter_mut().rev().for_each(|file| {
+        let size = file.range.end - file.range.start;
+        let Some(free) = free_space.first_fit(size) else {
+            return;
+        };
+        // files only move to the left
+        if free.start >= file.range.start {
+            return;
         }
-        right_index -= 1;
-    }
+        let moved = free.start..free.start + size;
+        free_space.remove(moved.clone());
+        free_space.insert(file.range.clone());
+        file.range = moved;
+    });
 
-    // print_blocks(&blocks);
+    // print_blocks(&files, &free_space);
 
-    blocks
+    files
         .iter()
-        .filter(|block| block.is_used)
-        .map(|block| {
-            // iterate over each "field" of the block
-            (0..block.size)
-                // multiply id by the total index of the field
-                .map(|i| block.id * (block.start + i))
-                // sum all up
-                .sum::<u64>()
+        .map(|file| {
+            // multiply id by the total index of every field of the block
+            file.range.clone().map(|i| file.id * i).sum::<u64>()
         })
         // sum all blocks as well
         .sum::<u64>()
 }
 
-fn print_blocks(blocks: &[Block]) {
-    blocks.iter().for_each(|block| {
-        (0..block.size).for_each(|i| {
-            if block.is_used {
-                print!("{:?}", block.id);
-            } else {
-                print!(".");
-            }
+fn print_blocks(files: &[Block], free_space: &RangeSet) {
+    let mut blocks: Vec<(Range<u64>, Option<u64>)> = files
+        .iter()
+        .map(|file| (file.range.clone(), Some(file.id)))
+        .chain(free_space.ranges().map(|range| (range, None)))
+        .collect();
+    blocks.sort_by_key(|(range, _)| range.start);
+
+    blocks.iter().for_each(|(range, id)| {
+        range.clone().for_each(|i| match id {
+            Some(id) => print!("{:?}", id),
+            None => print!("."),
         });
         print!("|");
     });
@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-use crate::input;
+use crate::{
+    arith::{add, mul},
+    digits, input,
+};
 
 #[test]
 pub fn test_all() {
@@ -30,10 +33,7 @@ pub fn run() {
 }
 
 fn process_1(input: &str) -> usize {
-    let mut data: Vec<String> = input
-        .split_ascii_whitespace()
-        .map(|s| s.to_owned())
-        .collect();
+    let mut data = parse(input);
     for i in 0..25 {
         data = iterate_once(&data);
     }
@@ -41,33 +41,27 @@ fn process_1(input: &str) -> usize {
 }
 
 fn process_1_by_recursion(input: &str) -> u64 {
-    let mut data: Vec<String> = input
-        .split_ascii_whitespace()
-        .map(|s| s.to_owned())
-        .collect();
-
-    let mut cache_hits = 0;
-    let mut cache_misses = 0;
-    let sum: u64 = data
-        .iter()
-        .map(|s| calculate_number_recursively(s.parse().unwrap_or(0), 25, &mut HashMap::new()))
-        .sum();
-    sum
+    count_by_recursion(input, 25)
 }
 
 fn process_2(input: &str) -> u64 {
-    let mut data: Vec<String> = input
+    count_by_recursion(input, 75)
+}
+
+fn parse(input: &str) -> Vec<u64> {
+    input
         .split_ascii_whitespace()
-        .map(|s| s.to_owned())
-        .collect();
+        .flat_map(|s| s.parse().ok())
+        .collect()
+}
 
-    let mut cache_hits = 0;
-    let mut cache_misses = 0;
-    let sum: u64 = data
-        .iter()
-        .map(|s| calculate_number_recursively(s.parse().unwrap_or(0), 75, &mut HashMap::new()))
-        .sum();
-    sum
+fn count_by_recursion(input: &str, steps: u64) -> u64 {
+    // the cache only depends on number and steps, so all stones can share it
+    let mut cache = HashMap::new();
+    parse(input)
+        .into_iter()
+        .map(|number| calculate_number_recursively(number, steps, &mut cache))
+        .fold(0, add)
 }
 
 fn calculate_number_recursively(
@@ -88,53 +82,30 @@ fn calculate_number_recursively(
             return *steps_cache.get(&number).unwrap();
         }
     }
-    let sum = match number {
-        0 => calculate_number_recursively(1, steps - 1, cache),
-        n if n.to_string().len() % 2 == 0 => {
-            let (left, right) = split_number(n);
-            calculate_number_recursively(left, steps - 1, cache)
-                + calculate_number_recursively(right, steps - 1, cache)
-        }
-        n => calculate_number_recursively(n * 2024, steps - 1, cache),
+    let sum = match (number, digits::split_half(number)) {
+        (0, _) => calculate_number_recursively(1, steps - 1, cache),
+        (_, Some((left, right))) => add(
+            calculate_number_recursively(left, steps - 1, cache),
+            calculate_number_recursively(right, steps - 1, cache),
+        ),
+        (n, None) => calculate_number_recursively(mul(n, 2024), steps - 1, cache),
     };
     cache.entry(steps).or_default().insert(number, sum);
     sum
 }
 
-fn split_number(number: u64) -> (u64, u64) {
-    let s = number.to_string();
-    let left: u64 = s[0..s.len() / 2].parse().unwrap_or(0);
-    let right: u64 = s[s.len() / 2..s.len()].parse().unwrap_or(0);
-    (left, right)
-}
-
-fn iterate_once(data: &[String]) -> Vec<String> {
-    let data: Vec<String> = data
+fn iterate_once(data: &[u64]) -> Vec<u64> {
+    let data: Vec<u64> = data
         .par_iter()
-        .filter(|s| !s.is_empty())
-        .flat_map(|s| {
-            if s == "0" {
-                return vec!["1".to_owned()];
+        .flat_map(|n| {
+            if *n == 0 {
+                return vec![1];
             }
-            if s.len() % 2 == 0 {
-                let len = s.len();
-                let half_len = len / 2;
-                let left = s[0..half_len].to_owned();
-                let right = &s[half_len..len];
-                // remove leading 0s. E.g. 012 -> 12
-                let right = right
-                    .parse::<u64>()
-                    .map(|n| n.to_string())
-                    .unwrap_or("0".to_owned());
+            // split halves are numbers, so leading 0s are gone already. E.g. 012 -> 12
+            if let Some((left, right)) = digits::split_half(*n) {
                 return vec![left, right];
             }
-            let new_s = s
-                .parse::<u64>()
-                .map(|n| n * 2024)
-                .map(|n| n.to_string())
-                .unwrap_or("".to_owned());
-
-            vec![new_s]
+            vec![mul(*n, 2024)]
         })
         .collect();
     data
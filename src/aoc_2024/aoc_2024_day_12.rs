@@ -2,7 +2,11 @@ use colored::{ColoredString, Colorize};
 use rand::{rngs::StdRng, RngCore, SeedableRng};
 use std::{cmp::Ordering, collections::HashMap};
 
-use crate::input;
+use crate::{
+    geometry,
+    grid::{ByteGrid, SparseGrid},
+    input,
+};
 
 #[test]
 pub fn test_all() {
@@ -91,63 +95,20 @@ fn process_2(input: &str) -> usize {
 }
 
 fn parse_map(input: &str) -> HashMap<char, Vec<Region>> {
-    // Recursively searching for neighbors (DFS) would probably be faster and simpler, but i want to try something different here. Declare each element as region and then merge regions of same kind.
+    let grid = ByteGrid::new(input);
     let mut map: HashMap<char, Vec<Region>> = HashMap::new();
 
-    // Two possible optimizations:
-    // 1. Merge regions directly on creation, saves the while loop
-    // 2. Merge regions by checking direct neighbor Points only. Would require some back reference from Point to Region.
-    // -> But it's fast enough for playing around as it is.
-
-    // Parse all elements into separate regions.
-    input
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .enumerate()
-        .for_each(|(y, line)| {
-            line.chars().enumerate().for_each(|(x, ch)| {
-                let region = Region::new(ch, Point::new_usize(x, y, ch));
-                map.entry(ch).or_default().push(region);
-            });
+    // neighbouring plants of the same kind are one region
+    grid.connected_components(|a, b| a == b)
+        .into_iter()
+        .for_each(|component| {
+            let ch = grid[component[0]] as char;
+            let points = component
+                .into_iter()
+                .map(|point| Point::new(point.x, point.y, ch))
+                .collect();
+            map.entry(ch).or_default().push(Region { ch, points });
         });
-
-    map.iter_mut().for_each(|(key, mut regions)| {
-        // repeat merge as long as there is something to merge. If latest iteration performed no merge, finish.
-        // iterate from last element, to remove like from a stack. Each element that is removed, is added to the end of the vec at the end. This reorders the whole vec, but since we go backwards, it is fine.
-        let mut i = regions.len();
-        while i > 0 {
-            // subtract first, to be able to stop at 0, so also start at len() instead of len() -1
-            i -= 1;
-
-            // need to remove the region, to make borrow checker happy. Maybe not the best solution.
-            let mut region = regions.remove(i);
-            // check removed region against all remaining ones
-            let mut j = regions.len();
-            let mut something_merged = false;
-            while j > 0 {
-                // same as for i
-                j -= 1;
-                let mut other_region = regions.remove(j);
-                if region.can_merge(&other_region) {
-                    // merge, do not add back to stack
-                    region.merge(other_region);
-                    something_merged = true;
-                } else {
-                    // cannot merge, add back to stack
-                    regions.push(other_region);
-                }
-            }
-            // add the previously removed region back. It might have multiple regions merged.
-            regions.push(region);
-
-            if something_merged {
-                // something was merged, go for another loop around, by resetting i to the full length
-                // because both the i iteration and the j iteration reorder the vec, we cannot simply just get the "next" i value.
-                // i can only count completey to 0, if nothing is merged anymore
-                i = regions.len();
-            }
-        }
-    });
     map
 }
 
@@ -157,8 +118,8 @@ fn print_regions(map: &HashMap<char, Vec<Region>>) {
     // always get the same colors
     let mut random: StdRng = StdRng::seed_from_u64(42);
 
-    // first allign them in a indexed map again
-    let mut hash_map: HashMap<i64, HashMap<i64, ColoredString>> = HashMap::new();
+    // first allign them in a grid again
+    let mut grid: SparseGrid<ColoredString> = SparseGrid::new();
     map.iter().for_each(|(key, regions)| {
         regions.iter().for_each(|region| {
             println!(
@@ -173,28 +134,16 @@ fn print_regions(map: &HashMap<char, Vec<Region>>) {
             let blue = (random.next_u64() % 255) as u8;
             let region_string = region.ch.to_string().custom_color((red, green, blue));
             region.points.iter().for_each(|point| {
-                hash_map
-                    .entry(point.y)
-                    .or_default()
-                    .entry(point.x)
-                    .insert_entry(region_string.clone());
+                grid.set(
+                    geometry::Point::new(point.x, point.y),
+                    region_string.clone(),
+                );
             })
         })
     });
 
-    // sort indices and print with color
-    let mut ys: Vec<&i64> = hash_map.keys().collect();
-    ys.sort();
-    for y in ys {
-        let line = hash_map.get(y).unwrap();
-        let mut xs: Vec<&i64> = line.keys().collect();
-        xs.sort();
-        for x in xs {
-            let s = line.get(x).unwrap();
-            print!("{}", s);
-        }
-        println!()
-    }
+    // print in order with color
+    print!("{}", grid.render(' ', |s| s.clone()));
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
@@ -204,12 +153,6 @@ struct Point {
     ch: char,
 }
 impl Point {
-    /// Is the other point next to this point in North, East, South or West direction
-    fn is_neighbour_4(&self, other: &Point) -> bool {
-        (self.y == other.y && self.x.abs_diff(other.x) == 1)
-            || (self.x == other.x && self.y.abs_diff(other.y) == 1)
-    }
-
     fn new_usize(x: usize, y: usize, ch: char) -> Self {
         Self::new(x as i64, y as i64, ch)
     }
@@ -280,29 +223,6 @@ struct Region {
 }
 
 impl Region {
-    fn can_merge(&self, other: &Region) -> bool {
-        if self.ch == other.ch {
-            return self.points.iter().any(|point| {
-                other
-                    .points
-                    .iter()
-                    .any(|other_point| point.is_neighbour_4(other_point))
-            });
-        }
-        false
-    }
-
-    /// Merge all points from the other region into this region.
-    /// Other region is dropped afterwards.
-    fn merge(&mut self, mut other: Region) {
-        self.points.append(&mut other.points);
-    }
-
-    fn new(ch: char, point: Point) -> Self {
-        let points = vec![point];
-        Self { ch, points }
-    }
-
     fn calulate_border(&self) -> Vec<Point> {
         let mut border: Vec<Point> = Vec::new();
         self.points.iter().for_each(|point| {
@@ -1,4 +1,9 @@
-use crate::input;
+use crate::{
+    arith::{add, mul},
+    input,
+    linear::{min_cost_solution, CostError},
+    puzzle_parse::PuzzleParse,
+};
 
 #[test]
 pub fn test_all() {
@@ -34,67 +39,45 @@ Prize: X=18641, Y=10279";
     assert_eq!(result, 77407675412647);
 }
 
+#[derive(Debug, PartialEq, PuzzleParse)]
+#[parse("Button A: X+{ax}, Y+{ay}\nButton B: X+{bx}, Y+{by}\nPrize: X={px}, Y={py}")]
+struct ClawMachine {
+    ax: i64,
+    ay: i64,
+    bx: i64,
+    by: i64,
+    px: i64,
+    py: i64,
+}
+
 fn process(input: &str, p_correction: i64) -> i64 {
-    let sum: i64 = input
-        .split("\n\n")
-        .map(|block| {
-            let lines: Vec<&str> = block.split("\n").collect();
-            if lines.len() < 3 {
-                return 0;
-            }
-            let axy: Vec<i64> = lines[0]
-                .split("Button A: X+")
-                .nth(1)
-                .unwrap()
-                .split(", Y+")
-                .map(|s| s.parse().unwrap())
-                .collect();
-            let ax = axy[0];
-            let ay = axy[1];
-            let bxy: Vec<i64> = lines[1]
-                .split("Button B: X+")
-                .nth(1)
-                .unwrap()
-                .split(", Y+")
-                .map(|s| s.parse().unwrap())
-                .collect();
-            let bx = bxy[0];
-            let by = bxy[1];
-            let pxy: Vec<i64> = lines[2]
-                .split("Prize: X=")
-                .nth(1)
-                .unwrap()
-                .split(", Y=")
-                .map(|s| s.parse().unwrap())
-                .collect();
-            let px = pxy[0] + p_correction;
-            let py = pxy[1] + p_correction;
-            // a * ax + b * bx = px
-            // a * ay + b * by = py
-            // a and b are unknowns
-            // a = (px - b * bx) / ax
-            // ((px - b * bx) / ax) * ay + b * by = py
-            // ((px - b * bx) / ax) + b * by / ay = py / ay
-            // (px - b * bx) + b * by * ax / ay = py * ax / ay
-            // px - b * bx + b * by * ax / ay = py * ax / ay
-            // px + b * ((by * ax / ay) - bx) = py * ax / ay
-            // b  = ((py * ax / ay) - px) / ((by * ax / ay) - bx)
-            // b  = ((py * ax / ay) - px) / ((by * ax - bx * ay) / ay)
-            // b  = ((py * ax / ay) - px) * ay / (by * ax - bx * ay)
-            // b  = (py * ax - px * ay) / (by * ax - bx * ay)
-            // If not cleanly integer divisible, solution does not work. It must be whole steps.
-            // -> (py * ax - px * ay) % (by * ax - bx * ay) == 0
-            // -> (px - b * bx) % ax == 0
-            if (py * ax - px * ay) % (by * ax - bx * ay) != 0 {
-                return 0;
-            }
-            let b = (py * ax - px * ay) / (by * ax - bx * ay);
-            if (px - b * bx) % ax != 0 {
-                return 0;
-            }
-            let a = (px - b * bx) / ax;
-            a * 3 + b
-        })
-        .sum();
+    let machines = ClawMachine::parse_sections(input)
+        .unwrap_or_else(|error| panic!("Invalid claw machine, {}", error));
+
+    let sum: i64 = machines
+        .into_iter()
+        .map(
+            |ClawMachine {
+                 ax,
+                 ay,
+                 bx,
+                 by,
+                 px,
+                 py,
+             }| {
+                let px = add(px, p_correction);
+                let py = add(py, p_correction);
+                // a * ax + b * bx = px
+                // a * ay + b * by = py
+                // a and b are unknowns, and must be whole, non-negative presses. A costs 3 tokens, B costs 1.
+                // If both buttons move in the same direction, there can be many ways, so take the cheapest.
+                match min_cost_solution(&[vec![ax, bx], vec![ay, by]], &[px, py], &[3, 1]) {
+                    Ok(presses) => add(mul(presses[0], 3), presses[1]),
+                    Err(CostError::NoSolution) => 0,
+                    Err(error) => panic!("Claw machine cannot be solved, {}", error),
+                }
+            },
+        )
+        .fold(0, add);
     sum
 }
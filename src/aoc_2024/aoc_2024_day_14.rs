@@ -1,6 +1,6 @@
 use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
 
-use crate::input;
+use crate::{cycle::brent, geometry::Point, grid::WrappingGrid, input, puzzle_parse::PuzzleParse};
 
 #[test]
 pub fn test_all() {
@@ -36,29 +36,42 @@ p=9,5 v=-3,-3
 
 fn process_1(input: &str, width: i64, height: i64) -> i64 {
     let mut robots = parse_robots(input);
+    let grid = WrappingGrid::new(width as usize, height as usize, ());
 
     robots.par_iter_mut().for_each(|robot| {
-        for i in 0..100 {
-            robot.step(width, height);
-        }
+        robot.position = grid.advance(robot.position, robot.velocity, 100);
     });
 
-    let (sum_1, sum_2, sum_3, sum_4) = calulate_quadrants(&robots, width, height);
+    let [sum_1, sum_2, sum_3, sum_4] =
+        grid.quadrant_counts(robots.iter().map(|robot| robot.position));
 
-    sum_1 * sum_2 * sum_3 * sum_4
+    (sum_1 * sum_2 * sum_3 * sum_4) as i64
 }
 
 fn process_2(input: &str, width: i64, height: i64) -> i64 {
     let mut robots = parse_robots(input);
-
-    for i in 0..10000 {
-        robots.iter_mut().for_each(|robot| {
-            robot.step(width, height);
-        });
-
-        let (sum_1, sum_2, sum_3, sum_4) = calulate_quadrants(&robots, width, height);
+    let grid = WrappingGrid::new(width as usize, height as usize, ());
+
+    let step = |robots: &Vec<Robot>| -> Vec<Robot> {
+        robots
+            .iter()
+            .map(|robot| Robot {
+                position: grid.advance(robot.position, robot.velocity, 1),
+                velocity: robot.velocity,
+            })
+            .collect()
+    };
+    // every robot is back at its start after at most width * height steps, so after one cycle every picture was seen.
+    // Brent only keeps two states around, instead of thousands of robot lists.
+    let cycle = brent(robots.clone(), step);
+
+    for i in 0..cycle.start + cycle.length {
+        robots = step(&robots);
+
+        let [sum_1, sum_2, sum_3, sum_4] =
+            grid.quadrant_counts(robots.iter().map(|robot| robot.position));
         // tree should create some sort of cluster. Search manually. Luckily 1/2 turns out to be a good limit
-        let len = robots.len() as i64 / 2;
+        let len = robots.len() / 2;
         if sum_1 >= len || sum_2 >= len || sum_3 >= len || sum_4 >= len {
             println!("Robots: {}", i + 1);
             print_robots(&robots, width, height);
@@ -68,85 +81,24 @@ fn process_2(input: &str, width: i64, height: i64) -> i64 {
     0
 }
 
-fn calulate_quadrants(robots: &[Robot], width: i64, height: i64) -> (i64, i64, i64, i64) {
-    let mut sum_1 = 0;
-    let mut sum_2 = 0;
-    let mut sum_3 = 0;
-    let mut sum_4 = 0;
-
+fn print_robots(robots: &[Robot], width: i64, height: i64) {
+    let mut grid = WrappingGrid::new(width as usize, height as usize, false);
     robots.iter().for_each(|robot| {
-        let mid_x = width / 2;
-        let mid_y = height / 2;
-        if robot.x < mid_x && robot.y < mid_y {
-            sum_1 += 1;
-        }
-        if robot.x > mid_x && robot.y < mid_y {
-            sum_2 += 1;
-        }
-        if robot.x < mid_x && robot.y > mid_y {
-            sum_3 += 1;
-        }
-        if robot.x > mid_x && robot.y > mid_y {
-            sum_4 += 1;
-        }
+        grid.set(robot.position, true);
     });
-    (sum_1, sum_2, sum_3, sum_4)
-}
-
-fn print_robots(robots: &[Robot], width: i64, height: i64) {
-    for y in 0..height {
-        for x in 0..width {
-            let opt = robots.iter().find(|robot| robot.x == x && robot.y == y);
-            match opt {
-                Some(_) => print!("X"),
-
-                None => print!(" "),
-            }
-        }
-        println!();
-    }
+    print!(
+        "{}",
+        grid.render(|is_robot| if *is_robot { 'X' } else { ' ' })
+    );
 }
 
 fn parse_robots(input: &str) -> Vec<Robot> {
-    let mut robots: Vec<Robot> = input
-        .split("\n")
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            let mid = line.find(" v=").unwrap();
-            let ps: Vec<i64> = line[2..mid]
-                .split(",")
-                .map(|s| s.parse().unwrap())
-                .collect();
-            let vs: Vec<i64> = line[mid + 3..]
-                .split(",")
-                .map(|s| s.parse().unwrap())
-                .collect();
-
-            Robot {
-                x: ps[0],
-                y: ps[1],
-                vx: vs[0],
-                vy: vs[1],
-            }
-        })
-        .collect();
-    robots
+    Robot::parse_lines(input).unwrap_or_else(|error| panic!("Invalid robot, {}", error))
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, PuzzleParse)]
+#[parse("p={position} v={velocity}")]
 struct Robot {
-    x: i64,
-    y: i64,
-    vx: i64,
-    vy: i64,
-}
-impl Robot {
-    fn step(&mut self, width: i64, height: i64) {
-        self.x = modulo(self.x + self.vx, width);
-        self.y = modulo(self.y + self.vy, height);
-    }
-}
-
-fn modulo(a: i64, b: i64) -> i64 {
-    ((a % b) + b) % b
+    position: Point,
+    velocity: Point,
 }
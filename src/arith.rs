@@ -0,0 +1,140 @@
+use std::{
+    fmt::Display,
+    ops::{Add, Mul, Sub},
+};
+
+#[test]
+pub fn test_arith() {
+    assert_eq!(add(2_i64, 3), 5);
+    assert_eq!(sub(2_u64, 2), 0);
+    assert_eq!(mul(-4_i64, 3), -12);
+    assert_eq!(pow(10_u64, 3), 1000);
+    assert_eq!(
+        Overflow::new::<i64>("*", i64::MAX, 2).to_string(),
+        "i64 overflow: 9223372036854775807 * 2"
+    );
+}
+
+#[cfg(feature = "checked-arithmetic")]
+#[test]
+pub fn test_arith_overflow() {
+    let result = std::panic::catch_unwind(|| mul(i64::MAX, 2));
+    let payload = result.unwrap_err();
+    assert_eq!(
+        payload.downcast_ref::<Overflow>(),
+        Some(&Overflow::new::<i64>("*", i64::MAX, 2))
+    );
+}
+
+/// Panic payload of an overflowing operation with the checked-arithmetic feature.
+/// The runner catches it and reports it as error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overflow {
+    pub operation: &'static str,
+    pub left: String,
+    pub right: String,
+    pub type_name: &'static str,
+}
+
+impl Overflow {
+    pub fn new<T: Display>(operation: &'static str, left: T, right: impl Display) -> Self {
+        Self {
+            operation,
+            left: left.to_string(),
+            right: right.to_string(),
+            type_name: std::any::type_name::<T>(),
+        }
+    }
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} overflow: {} {} {}",
+            self.type_name, self.left, self.operation, self.right
+        )
+    }
+}
+
+/// Integers, that the arithmetic helpers work with.
+pub trait Integer:
+    Copy + Display + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_pow(self, exp: u32) -> Option<Self>;
+    fn pow(self, exp: u32) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, other)
+                }
+                fn checked_pow(self, exp: u32) -> Option<Self> {
+                    <$t>::checked_pow(self, exp)
+                }
+                fn pow(self, exp: u32) -> Self {
+                    <$t>::pow(self, exp)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i32, i64, i128, u32, u64, u128, usize);
+
+// Without the feature these are the plain operators, so release builds stay as fast as before, and wrap silently.
+// With the feature every operation is checked, and an overflow panics with an Overflow payload.
+
+#[inline]
+pub fn add<T: Integer>(a: T, b: T) -> T {
+    if cfg!(feature = "checked-arithmetic") {
+        a.checked_add(b).unwrap_or_else(|| overflow("+", a, b))
+    } else {
+        a + b
+    }
+}
+
+#[inline]
+pub fn sub<T: Integer>(a: T, b: T) -> T {
+    if cfg!(feature = "checked-arithmetic") {
+        a.checked_sub(b).unwrap_or_else(|| overflow("-", a, b))
+    } else {
+        a - b
+    }
+}
+
+#[inline]
+pub fn mul<T: Integer>(a: T, b: T) -> T {
+    if cfg!(feature = "checked-arithmetic") {
+        a.checked_mul(b).unwrap_or_else(|| overflow("*", a, b))
+    } else {
+        a * b
+    }
+}
+
+#[inline]
+pub fn pow<T: Integer>(base: T, exp: u32) -> T {
+    if cfg!(feature = "checked-arithmetic") {
+        base.checked_pow(exp)
+            .unwrap_or_else(|| overflow("^", base, exp))
+    } else {
+        base.pow(exp)
+    }
+}
+
+#[cold]
+fn overflow<T: Display>(operation: &'static str, left: T, right: impl Display) -> ! {
+    std::panic::panic_any(Overflow::new::<T>(operation, left, right))
+}
@@ -0,0 +1,467 @@
+use std::ops::Range;
+
+#[test]
+pub fn test_arrangements() {
+    // first position changes fastest, like an odometer read from the left
+    let all: Vec<Vec<usize>> = cartesian_power(2, 2).collect();
+    assert_eq!(all, vec![vec![0, 0], vec![1, 0], vec![0, 1], vec![1, 1]]);
+    assert_eq!(cartesian_power(3, 4).count(), 3_usize.pow(4));
+    assert_eq!(cartesian_power(3, 0).count(), 1);
+
+    let all: Vec<Vec<usize>> = permutations(3).collect();
+    assert_eq!(
+        all,
+        vec![
+            vec![0, 1, 2],
+            vec![0, 2, 1],
+            vec![1, 0, 2],
+            vec![1, 2, 0],
+            vec![2, 0, 1],
+            vec![2, 1, 0]
+        ]
+    );
+
+    let all: Vec<Vec<usize>> = combinations(4, 2).collect();
+    assert_eq!(
+        all,
+        vec![
+            vec![0, 1],
+            vec![0, 2],
+            vec![0, 3],
+            vec![1, 2],
+            vec![1, 3],
+            vec![2, 3]
+        ]
+    );
+    assert_eq!(combinations(3, 4).count(), 0);
+
+    let all: Vec<Vec<usize>> = combinations_with_replacement(3, 2).collect();
+    assert_eq!(
+        all,
+        vec![
+            vec![0, 0],
+            vec![0, 1],
+            vec![0, 2],
+            vec![1, 1],
+            vec![1, 2],
+            vec![2, 2]
+        ]
+    );
+
+    let all: Vec<Vec<usize>> = subsets(2).collect();
+    assert_eq!(all, vec![vec![], vec![0], vec![1], vec![0, 1]]);
+
+    // lending, the same buffer is reused for every arrangement
+    let mut arrangements = combinations(5, 3);
+    let mut count = 0;
+    while let Some(selection) = arrangements.next_ref() {
+        assert_eq!(selection.len(), 3);
+        count += 1;
+    }
+    assert_eq!(count, 10);
+}
+
+#[test]
+pub fn test_rank_and_split() {
+    fn check<A: Arrangement + Clone>(arrangements: Arrangements<A>) {
+        let all: Vec<Vec<usize>> = arrangements.clone().collect();
+        all.iter().enumerate().for_each(|(rank, selection)| {
+            assert_eq!(arrangements.rank(selection), rank);
+            assert_eq!(&arrangements.unrank(rank), selection);
+        });
+        // splitting covers every arrangement exactly once, in order
+        let parts = arrangements.split(4);
+        assert_eq!(parts.len(), 4);
+        let joined: Vec<Vec<usize>> = parts.into_iter().flatten().collect();
+        assert_eq!(joined, all);
+    }
+    check(cartesian_power(3, 3));
+    check(permutations(4));
+    check(combinations(6, 3));
+    check(combinations_with_replacement(4, 3));
+    check(subsets(5));
+
+    // a partly used iterator only splits what is left
+    let mut arrangements = permutations(3);
+    arrangements.next();
+    let parts = arrangements.split(2);
+    assert_eq!(parts.iter().map(|part| part.len()).sum::<usize>(), 5);
+}
+
+/// A kind of arrangement of indices, that can be numbered in a fixed order.
+/// Ranks go from 0 to count, so a range of ranks can be handed to each thread, see [`Arrangements::split`].
+pub trait Arrangement {
+    /// Number of arrangements.
+    fn count(&self) -> usize;
+    /// Write the arrangement with the given rank into the selection.
+    fn unrank(&self, rank: usize, selection: &mut Vec<usize>);
+    /// Position of the arrangement in the order.
+    fn rank(&self, selection: &[usize]) -> usize;
+    /// Step the selection to the next arrangement, which has the given rank.
+    /// Usually cheaper than unranking from scratch.
+    fn advance(&self, rank: usize, selection: &mut Vec<usize>) {
+        self.unrank(rank, selection);
+    }
+}
+
+/// Iterates over a range of ranks of an arrangement. The selection buffer is reused,
+/// so [`Arrangements::next_ref`] does not allocate. As Iterator, every item is a new vec.
+#[derive(Debug, Clone)]
+pub struct Arrangements<A> {
+    kind: A,
+    selection: Vec<usize>,
+    next: usize,
+    end: usize,
+    started: bool,
+}
+
+impl<A: Arrangement + Clone> Arrangements<A> {
+    pub fn new(kind: A) -> Self {
+        let end = kind.count();
+        Self::with_range(kind, 0..end)
+    }
+
+    /// Only the arrangements with a rank in the range.
+    pub fn with_range(kind: A, range: Range<usize>) -> Self {
+        Self {
+            kind,
+            selection: Vec::new(),
+            next: range.start,
+            end: range.end,
+            started: false,
+        }
+    }
+
+    /// Lending version of next. The slice is only valid until the next call.
+    pub fn next_ref(&mut self) -> Option<&[usize]> {
+        if self.next >= self.end {
+            return None;
+        }
+        if self.started {
+            self.kind.advance(self.next, &mut self.selection);
+        } else {
+            self.kind.unrank(self.next, &mut self.selection);
+            self.started = true;
+        }
+        self.next += 1;
+        Some(&self.selection)
+    }
+
+    /// Number of arrangements left.
+    pub fn len(&self) -> usize {
+        self.end.saturating_sub(self.next)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn rank(&self, selection: &[usize]) -> usize {
+        self.kind.rank(selection)
+    }
+
+    pub fn unrank(&self, rank: usize) -> Vec<usize> {
+        let mut selection = Vec::new();
+        self.kind.unrank(rank, &mut selection);
+        selection
+    }
+
+    /// Split the remaining arrangements into parts of about the same size, e.g. for `into_par_iter`.
+    pub fn split(&self, parts: usize) -> Vec<Self> {
+        let parts = parts.max(1);
+        let len = self.len();
+        (0..parts)
+            .map(|part| {
+                let start = self.next + len * part / parts;
+                let end = self.next + len * (part + 1) / parts;
+                Self::with_range(self.kind.clone(), start..end)
+            })
+            .collect()
+    }
+}
+
+impl<A: Arrangement + Clone> Iterator for Arrangements<A> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        self.next_ref().map(|selection| selection.to_vec())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<A: Arrangement + Clone> ExactSizeIterator for Arrangements<A> {}
+
+/// Every sequence of the given length with values from 0 to base, e.g. every choice of operators between values.
+pub fn cartesian_power(base: usize, length: usize) -> Arrangements<CartesianPower> {
+    Arrangements::new(CartesianPower { base, length })
+}
+
+/// Every order of the indices 0 to n, in lexicographic order.
+pub fn permutations(n: usize) -> Arrangements<Permutations> {
+    Arrangements::new(Permutations { n })
+}
+
+/// Every set of k different indices from 0 to n, ascending, in lexicographic order.
+pub fn combinations(n: usize, k: usize) -> Arrangements<Combinations> {
+    Arrangements::new(Combinations { n, k })
+}
+
+/// Every multiset of k indices from 0 to n, ascending, in lexicographic order.
+pub fn combinations_with_replacement(
+    n: usize,
+    k: usize,
+) -> Arrangements<CombinationsWithReplacement> {
+    Arrangements::new(CombinationsWithReplacement { n, k })
+}
+
+/// Every subset of the indices 0 to n, ordered by their bit mask.
+pub fn subsets(n: usize) -> Arrangements<Subsets> {
+    Arrangements::new(Subsets { n })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CartesianPower {
+    pub base: usize,
+    pub length: usize,
+}
+
+impl Arrangement for CartesianPower {
+    fn count(&self) -> usize {
+        self.base
+            .checked_pow(self.length as u32)
+            .expect("Too many arrangements to count.")
+    }
+
+    fn unrank(&self, rank: usize, selection: &mut Vec<usize>) {
+        selection.clear();
+        let mut rank = rank;
+        for _ in 0..self.length {
+            selection.push(rank % self.base);
+            rank /= self.base;
+        }
+    }
+
+    fn rank(&self, selection: &[usize]) -> usize {
+        selection
+            .iter()
+            .rev()
+            .fold(0, |rank, value| rank * self.base + value)
+    }
+
+    fn advance(&self, rank: usize, selection: &mut Vec<usize>) {
+        for value in selection.iter_mut() {
+            *value += 1;
+            if *value < self.base {
+                return;
+            }
+            // carry over to the next position
+            *value = 0;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permutations {
+    pub n: usize,
+}
+
+impl Arrangement for Permutations {
+    fn count(&self) -> usize {
+        factorial(self.n)
+    }
+
+    fn unrank(&self, rank: usize, selection: &mut Vec<usize>) {
+        // the rank is written in the factorial number system, every digit picks one of the unused indices
+        let mut available: Vec<usize> = (0..self.n).collect();
+        let mut rank = rank;
+        selection.clear();
+        for i in 0..self.n {
+            let place = factorial(self.n - 1 - i);
+            selection.push(available.remove(rank / place));
+            rank %= place;
+        }
+    }
+
+    fn rank(&self, selection: &[usize]) -> usize {
+        selection
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let smaller_later = selection[i + 1..]
+                    .iter()
+                    .filter(|later| *later < value)
+                    .count();
+                smaller_later * factorial(self.n - 1 - i)
+            })
+            .sum()
+    }
+
+    fn advance(&self, rank: usize, selection: &mut Vec<usize>) {
+        // next lexicographic permutation: increase the rightmost position that can be increased, then sort the rest
+        let Some(i) = (0..selection.len().saturating_sub(1))
+            .rev()
+            .find(|i| selection[*i] < selection[*i + 1])
+        else {
+            return;
+        };
+        let j = (i + 1..selection.len())
+            .rev()
+            .find(|j| selection[*j] > selection[i])
+            .unwrap();
+        selection.swap(i, j);
+        selection[i + 1..].reverse();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Combinations {
+    pub n: usize,
+    pub k: usize,
+}
+
+impl Arrangement for Combinations {
+    fn count(&self) -> usize {
+        binomial(self.n, self.k)
+    }
+
+    fn unrank(&self, rank: usize, selection: &mut Vec<usize>) {
+        // skip whole blocks of combinations, that start with a smaller value
+        let mut rank = rank;
+        let mut value = 0;
+        selection.clear();
+        for i in 0..self.k {
+            loop {
+                let block = binomial(self.n - value - 1, self.k - i - 1);
+                if rank < block {
+                    break;
+                }
+                rank -= block;
+                value += 1;
+            }
+            selection.push(value);
+            value += 1;
+        }
+    }
+
+    fn rank(&self, selection: &[usize]) -> usize {
+        let mut rank = 0;
+        let mut start = 0;
+        for (i, value) in selection.iter().enumerate() {
+            rank += (start..*value)
+                .map(|smaller| binomial(self.n - smaller - 1, self.k - i - 1))
+                .sum::<usize>();
+            start = value + 1;
+        }
+        rank
+    }
+
+    fn advance(&self, rank: usize, selection: &mut Vec<usize>) {
+        let Some(i) = (0..self.k)
+            .rev()
+            .find(|i| selection[*i] < self.n - self.k + i)
+        else {
+            return;
+        };
+        selection[i] += 1;
+        for j in i + 1..self.k {
+            selection[j] = selection[j - 1] + 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CombinationsWithReplacement {
+    pub n: usize,
+    pub k: usize,
+}
+
+impl CombinationsWithReplacement {
+    /// Adding i to the i-th value turns every multiset into a set of n + k - 1, so ranks can be reused.
+    fn as_combinations(&self) -> Combinations {
+        Combinations {
+            n: (self.n + self.k).saturating_sub(1),
+            k: self.k,
+        }
+    }
+}
+
+impl Arrangement for CombinationsWithReplacement {
+    fn count(&self) -> usize {
+        if self.n == 0 {
+            return (self.k == 0) as usize;
+        }
+        self.as_combinations().count()
+    }
+
+    fn unrank(&self, rank: usize, selection: &mut Vec<usize>) {
+        self.as_combinations().unrank(rank, selection);
+        selection
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, value)| *value -= i);
+    }
+
+    fn rank(&self, selection: &[usize]) -> usize {
+        let shifted: Vec<usize> = selection
+            .iter()
+            .enumerate()
+            .map(|(i, value)| value + i)
+            .collect();
+        self.as_combinations().rank(&shifted)
+    }
+
+    fn advance(&self, rank: usize, selection: &mut Vec<usize>) {
+        let Some(i) = (0..self.k).rev().find(|i| selection[*i] + 1 < self.n) else {
+            return;
+        };
+        selection[i] += 1;
+        let value = selection[i];
+        selection[i + 1..].fill(value);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subsets {
+    pub n: usize,
+}
+
+impl Arrangement for Subsets {
+    fn count(&self) -> usize {
+        1_usize
+            .checked_shl(self.n as u32)
+            .expect("Too many arrangements to count.")
+    }
+
+    fn unrank(&self, rank: usize, selection: &mut Vec<usize>) {
+        selection.clear();
+        selection.extend((0..self.n).filter(|i| rank & (1 << i) != 0));
+    }
+
+    fn rank(&self, selection: &[usize]) -> usize {
+        selection.iter().map(|i| 1 << i).sum()
+    }
+}
+
+/// n!, panics if it does not fit into an usize.
+pub fn factorial(n: usize) -> usize {
+    (1..=n)
+        .try_fold(1_usize, |product, i| product.checked_mul(i))
+        .expect("Factorial does not fit into an usize.")
+}
+
+/// n choose k, 0 for k > n. Panics if it does not fit into an usize.
+pub fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    // multiply and divide alternately, every intermediate result is a binomial itself
+    let k = k.min(n - k);
+    (0..k)
+        .try_fold(1_usize, |result, i| {
+            result.checked_mul(n - i).map(|product| product / (i + 1))
+        })
+        .expect("Binomial does not fit into an usize.")
+}
@@ -0,0 +1,158 @@
+use std::{collections::HashMap, hash::Hash};
+
+#[test]
+pub fn test_cycle() {
+    // 0, 1, 2, 3, 4, 5, 6, 3, 4, ...
+    let step = |x: &u64| if *x < 6 { x + 1 } else { 3 };
+    let expected = Cycle {
+        start: 3,
+        length: 4,
+    };
+
+    assert_eq!(find_cycle(0, step), expected);
+    assert_eq!(brent(0, step), expected);
+    assert_eq!(floyd(0, step), expected);
+
+    assert_eq!(expected.reduce(2), 2);
+    assert_eq!(expected.reduce(7), 3);
+    assert_eq!(fast_forward(0, step, 5), 5);
+    assert_eq!(fast_forward(0, step, 1_000_000_000), 4);
+
+    // a simulation that ends has no cycle
+    assert_eq!(
+        find_cycle_until(0, |x| if *x < 10 { Some(x + 1) } else { None }),
+        None
+    );
+    assert_eq!(
+        find_cycle_until(0, |x| Some(step(x))),
+        Some(expected.clone())
+    );
+}
+
+/// A cycle in a sequence of states x0, f(x0), f(f(x0)), ...
+/// The state at step `start` is the first one, that repeats, and it repeats every `length` steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step, that has the same state as the given step.
+    pub fn reduce(&self, step: usize) -> usize {
+        if step < self.start {
+            return step;
+        }
+        self.start + (step - self.start) % self.length
+    }
+}
+
+/// Find the first repeated state by remembering every state. Fastest option, if states are cheap to hash.
+/// Never returns, if the sequence does not repeat.
+pub fn find_cycle<S: Hash + Eq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    find_cycle_until(initial, |state| Some(step(state))).unwrap()
+}
+
+/// Like [`find_cycle`], for simulations that can end, e.g. a guard walking out of the map.
+/// The step returns None at the end, and then there is no cycle.
+pub fn find_cycle_until<S: Hash + Eq + Clone>(
+    initial: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+) -> Option<Cycle> {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut state = initial;
+    for index in 0.. {
+        if let Some(start) = seen.insert(state.clone(), index) {
+            return Some(Cycle {
+                start,
+                length: index - start,
+            });
+        }
+        state = step(&state)?;
+    }
+    unreachable!()
+}
+
+/// Brent's algorithm. Only keeps two states in memory and needs only equality, so it works for big states.
+/// Usually needs less steps than [`floyd`]. Never returns, if the sequence does not repeat.
+pub fn brent<S: PartialEq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // find the length: the tortoise waits at powers of two, until the hare comes around
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // with the hare one cycle ahead, both meet at the start
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Floyd's tortoise and hare. Same as [`brent`], but the hare runs at double speed.
+pub fn floyd<S: PartialEq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    // the meeting point is a multiple of the length away from the start
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { start, length }
+}
+
+/// The state after n steps. Simulates until n is reached or a state repeats, and then skips all full cycles.
+pub fn fast_forward<S: Hash + Eq + Clone>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    n: usize,
+) -> S {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut states: Vec<S> = Vec::new();
+    let mut state = initial;
+    while states.len() < n {
+        if let Some(start) = seen.insert(state.clone(), states.len()) {
+            let cycle = Cycle {
+                start,
+                length: states.len() - start,
+            };
+            return states[cycle.reduce(n)].clone();
+        }
+        states.push(state.clone());
+        state = step(&state);
+    }
+    state
+}
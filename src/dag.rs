@@ -0,0 +1,233 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+#[test]
+pub fn test_dag() {
+    // 1 -> 2 -> 4, 1 -> 3 -> 4, 3 -> 5, 4 -> 6, 5 is a dead end
+    let successors = |n: &u32| -> Vec<u32> {
+        match n {
+            1 => vec![2, 3],
+            2 => vec![4],
+            3 => vec![4, 5],
+            4 => vec![6],
+            _ => vec![],
+        }
+    };
+    let is_sink = |n: &u32| *n == 6 || *n == 2;
+
+    // 2 is a sink itself, so the path through 2 ends there
+    assert_eq!(count_paths([1], successors, is_sink), 2);
+    assert_eq!(count_paths([1, 3], successors, is_sink), 3);
+
+    let mut reachability = SinkReachability::new(successors, is_sink);
+    assert_eq!(reachability.sinks(&1), &HashSet::from([2, 6]));
+    assert_eq!(reachability.sinks(&5), &HashSet::new());
+
+    let paths: Vec<Vec<u32>> = paths(1, successors, is_sink).collect();
+    assert_eq!(paths, vec![vec![1, 2], vec![1, 3, 4, 6]]);
+}
+
+/// Number of distinct paths from the sources to any sink. Each source is counted separately, but shares the memo.
+/// See [`PathCounter`].
+pub fn count_paths<S, I>(
+    sources: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_sink: impl FnMut(&S) -> bool,
+) -> u64
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = S>,
+{
+    let mut counter = PathCounter::new(successors, is_sink);
+    sources
+        .into_iter()
+        .map(|source| counter.count(&source))
+        .sum()
+}
+
+/// Counts paths to sinks in a directed acyclic graph, without building the paths.
+/// The count of every node is the sum of the counts of its successors, so each node is calculated only once.
+/// A path ends at the first sink it reaches. Nodes without successors, that are no sinks, are dead ends.
+pub struct PathCounter<S, F, G> {
+    successors: F,
+    is_sink: G,
+    memo: HashMap<S, u64>,
+}
+
+impl<S, I, F, G> PathCounter<S, F, G>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = S>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    pub fn new(successors: F, is_sink: G) -> Self {
+        Self {
+            successors,
+            is_sink,
+            memo: HashMap::new(),
+        }
+    }
+
+    pub fn count(&mut self, source: &S) -> u64 {
+        *fold(
+            &mut self.memo,
+            source,
+            &mut self.successors,
+            &mut self.is_sink,
+            |_| 1,
+            |counts| counts.into_iter().sum(),
+        )
+    }
+}
+
+/// The set of sinks, that can be reached from a node of a directed acyclic graph.
+/// Sets are memoised per node, so asking for many sources of the same graph is cheap.
+pub struct SinkReachability<S, F, G> {
+    successors: F,
+    is_sink: G,
+    memo: HashMap<S, HashSet<S>>,
+}
+
+impl<S, I, F, G> SinkReachability<S, F, G>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = S>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    pub fn new(successors: F, is_sink: G) -> Self {
+        Self {
+            successors,
+            is_sink,
+            memo: HashMap::new(),
+        }
+    }
+
+    pub fn sinks(&mut self, source: &S) -> &HashSet<S> {
+        fold(
+            &mut self.memo,
+            source,
+            &mut self.successors,
+            &mut self.is_sink,
+            |sink| HashSet::from([sink.clone()]),
+            |sets| sets.into_iter().flatten().cloned().collect(),
+        )
+    }
+}
+
+/// Calculate a value for every node from the values of its successors, bottom up and memoised.
+/// Iterative, so deep graphs cannot overflow the stack. Panics if a cycle is found.
+fn fold<'a, S, T, I>(
+    memo: &'a mut HashMap<S, T>,
+    source: &S,
+    successors: &mut impl FnMut(&S) -> I,
+    is_sink: &mut impl FnMut(&S) -> bool,
+    sink_value: impl Fn(&S) -> T,
+    combine: impl Fn(Vec<&T>) -> T,
+) -> &'a T
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = S>,
+{
+    // nodes with their successors are on the stack twice: first to expand them, then to combine the successor values.
+    // The nodes waiting to be combined are exactly the current path, which is used to detect cycles.
+    let mut stack: Vec<(S, Option<Vec<S>>)> = vec![(source.clone(), None)];
+    let mut current_path: HashSet<S> = HashSet::new();
+
+    while let Some((node, expanded)) = stack.pop() {
+        if let Some(node_successors) = expanded {
+            let value = combine(
+                node_successors
+                    .iter()
+                    .map(|successor| &memo[successor])
+                    .collect(),
+            );
+            current_path.remove(&node);
+            memo.insert(node, value);
+            continue;
+        }
+        if memo.contains_key(&node) {
+            continue;
+        }
+        if is_sink(&node) {
+            let value = sink_value(&node);
+            memo.insert(node, value);
+            continue;
+        }
+        assert!(
+            current_path.insert(node.clone()),
+            "The graph contains a cycle, paths cannot be counted."
+        );
+
+        let node_successors: Vec<S> = successors(&node).into_iter().collect();
+        let open: Vec<S> = node_successors
+            .iter()
+            .filter(|successor| !memo.contains_key(successor))
+            .cloned()
+            .collect();
+        stack.push((node, Some(node_successors)));
+        stack.extend(open.into_iter().map(|successor| (successor, None)));
+    }
+    &memo[source]
+}
+
+/// Lazily walk every path from the source to a sink, depth first. Only use it if the actual paths matter,
+/// there can be exponentially many. Each path is returned as a new vec, from the source to the sink.
+pub fn paths<S, I, F, G>(source: S, successors: F, is_sink: G) -> Paths<S, F, G>
+where
+    S: Clone,
+    I: IntoIterator<Item = S>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    Paths {
+        stack: vec![(source, 0)],
+        path: Vec::new(),
+        successors,
+        is_sink,
+    }
+}
+
+/// Iterator returned by [`paths`].
+pub struct Paths<S, F, G> {
+    // every node with its depth in the path
+    stack: Vec<(S, usize)>,
+    path: Vec<S>,
+    successors: F,
+    is_sink: G,
+}
+
+impl<S, I, F, G> Iterator for Paths<S, F, G>
+where
+    S: Clone,
+    I: IntoIterator<Item = S>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    type Item = Vec<S>;
+
+    fn next(&mut self) -> Option<Vec<S>> {
+        while let Some((node, depth)) = self.stack.pop() {
+            // going back up the tree drops the nodes of the previous branch
+            self.path.truncate(depth);
+            self.path.push(node.clone());
+
+            if (self.is_sink)(&node) {
+                return Some(self.path.clone());
+            }
+
+            let index = self.stack.len();
+            self.stack.extend(
+                (self.successors)(&node)
+                    .into_iter()
+                    .map(|successor| (successor, depth + 1)),
+            );
+            // first successor is walked first
+            self.stack[index..].reverse();
+        }
+        None
+    }
+}
@@ -0,0 +1,136 @@
+use crate::arith::{add, mul};
+
+#[test]
+pub fn test_digits() {
+    assert_eq!(count(0), 1);
+    assert_eq!(count(9), 1);
+    assert_eq!(count(10), 2);
+    assert_eq!(count(u64::MAX), 20);
+
+    assert_eq!(split_half(253000), Some((253, 0)));
+    assert_eq!(split_half(1000), Some((10, 0)));
+    assert_eq!(split_half(123), None);
+
+    assert_eq!(concat(12, 345), 12345);
+    assert_eq!(concat(12, 0), 120);
+    assert_eq!(checked_concat(u64::MAX / 10, 99), None);
+    // 20 digits leave no room for anything but 0 in front
+    assert_eq!(checked_concat(1, u64::MAX), None);
+    assert_eq!(checked_concat(0, u64::MAX), Some(u64::MAX));
+    assert_eq!(concat(0, u64::MAX), u64::MAX);
+
+    assert_eq!(iter(4096).collect::<Vec<u8>>(), vec![4, 0, 9, 6]);
+    assert_eq!(iter(0).collect::<Vec<u8>>(), vec![0]);
+    assert_eq!(reverse(1200), 21);
+
+    assert_eq!(to_base(10, 2), vec![1, 0, 1, 0]);
+    assert_eq!(to_base(0, 16), vec![0]);
+    assert_eq!(from_base(&[1, 0, 1, 0], 2), 10);
+    assert_eq!(from_base(&to_base(u64::MAX, 7), 7), u64::MAX);
+}
+
+#[cfg(feature = "checked-arithmetic")]
+#[test]
+pub fn test_concat_overflow() {
+    let result = std::panic::catch_unwind(|| concat(1, u64::MAX));
+    assert!(result
+        .unwrap_err()
+        .downcast_ref::<crate::arith::Overflow>()
+        .is_some());
+}
+
+/// 10^0 to 10^19, every power of ten that fits into an u64.
+const POWERS_OF_TEN: [u64; 20] = {
+    let mut powers = [1; 20];
+    let mut i = 1;
+    while i < 20 {
+        powers[i] = powers[i - 1] * 10;
+        i += 1;
+    }
+    powers
+};
+
+/// 10^exp from a table. Panics for exp > 19, which does not fit into an u64.
+pub fn pow10(exp: u32) -> u64 {
+    POWERS_OF_TEN[exp as usize]
+}
+
+/// Number of decimal digits, 0 has one digit.
+pub fn count(n: u64) -> u32 {
+    POWERS_OF_TEN.partition_point(|power| *power <= n).max(1) as u32
+}
+
+/// Split a number with an even count of digits into its left and right half, e.g. 1000 into 10 and 0.
+/// None for an odd count of digits.
+pub fn split_half(n: u64) -> Option<(u64, u64)> {
+    let digits = count(n);
+    if !digits.is_multiple_of(2) {
+        return None;
+    }
+    let divisor = pow10(digits / 2);
+    Some((n / divisor, n % divisor))
+}
+
+/// Append the digits of b to a, e.g. 12 and 345 to 12345.
+/// Goes through the checked-arithmetic helpers, so overflows are only detected with that feature.
+pub fn concat(a: u64, b: u64) -> u64 {
+    let shifted = match count(b) {
+        // 10^20 does not fit into an u64, so shift in two steps and let the helpers report the overflow
+        20 => mul(mul(a, pow10(19)), 10),
+        digits => mul(a, pow10(digits)),
+    };
+    add(shifted, b)
+}
+
+/// Like [`concat`], None if the result does not fit into an u64.
+pub fn checked_concat(a: u64, b: u64) -> Option<u64> {
+    let shifted = match count(b) {
+        20 => a.checked_mul(pow10(19))?.checked_mul(10)?,
+        digits => a.checked_mul(pow10(digits))?,
+    };
+    shifted.checked_add(b)
+}
+
+/// Decimal digits, most significant first.
+pub fn iter(n: u64) -> impl Iterator<Item = u8> {
+    (0..count(n))
+        .rev()
+        .map(move |position| (n / pow10(position) % 10) as u8)
+}
+
+/// Digits in reverse order, e.g. 1200 turns into 21. Panics, if the result does not fit into an u64.
+pub fn reverse(n: u64) -> u64 {
+    let mut n = n;
+    let mut reversed: u64 = 0;
+    while n > 0 {
+        reversed = reversed
+            .checked_mul(10)
+            .and_then(|reversed| reversed.checked_add(n % 10))
+            .expect("Reversed number does not fit into an u64.");
+        n /= 10;
+    }
+    reversed
+}
+
+/// Digits in any base from 2 to 256, most significant first. 0 is a single 0 digit.
+pub fn to_base(n: u64, base: u64) -> Vec<u8> {
+    assert!((2..=256).contains(&base), "Base must be between 2 and 256.");
+    let mut n = n;
+    let mut digits = Vec::new();
+    loop {
+        digits.push((n % base) as u8);
+        n /= base;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    digits
+}
+
+/// Number from digits in any base, most significant first.
+pub fn from_base(digits: &[u8], base: u64) -> u64 {
+    digits
+        .iter()
+        .fold(0, |n, digit| add(mul(n, base), *digit as u64))
+}
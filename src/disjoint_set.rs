@@ -0,0 +1,258 @@
+use std::{collections::HashMap, hash::Hash};
+
+#[test]
+pub fn test_disjoint_set() {
+    let mut set = DisjointSet::new(6);
+    assert_eq!(set.component_count(), 6);
+
+    assert!(set.union(0, 1));
+    assert!(set.union(1, 2));
+    assert!(!set.union(0, 2));
+    assert!(set.union(4, 5));
+
+    assert!(set.same(0, 2));
+    assert!(!set.same(0, 3));
+    assert_eq!(set.size_of(1), 3);
+    assert_eq!(set.component_count(), 3);
+    assert_eq!(set.components(), vec![vec![0, 1, 2], vec![3], vec![4, 5]]);
+    assert_eq!(set.component_sizes(), vec![3, 1, 2]);
+
+    let index = set.add();
+    assert_eq!(index, 6);
+    assert_eq!(set.component_count(), 4);
+}
+
+#[test]
+pub fn test_keyed_disjoint_set() {
+    let mut set: KeyedDisjointSet<&str> = KeyedDisjointSet::new();
+    set.union("a", "b");
+    set.union("c", "d");
+    set.insert("e");
+    set.union("d", "b");
+
+    assert!(set.same(&"a", &"c"));
+    assert!(!set.same(&"a", &"e"));
+    // unknown keys are in no component at all
+    assert!(!set.same(&"a", &"x"));
+    assert_eq!(set.size_of(&"a"), Some(4));
+    assert_eq!(set.components(), vec![vec!["a", "b", "c", "d"], vec!["e"]]);
+}
+
+/// Union find over the indices 0..len. Uses path compression and union by size,
+/// so every operation is practically constant time.
+#[derive(Debug, Clone, Default)]
+pub struct DisjointSet {
+    parents: Vec<usize>,
+    // only valid for roots
+    sizes: Vec<usize>,
+    component_count: usize,
+}
+
+impl DisjointSet {
+    /// Every index starts in its own component.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            component_count: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Add a new index in its own component and return it.
+    pub fn add(&mut self) -> usize {
+        let index = self.parents.len();
+        self.parents.push(index);
+        self.sizes.push(1);
+        self.component_count += 1;
+        index
+    }
+
+    /// The representative of the component. Only stable until the next union.
+    pub fn find(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        // point the whole path directly to the root
+        let mut current = index;
+        while self.parents[current] != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    /// Merge both components. Returns false, if they were already the same.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let a = self.find(a);
+        let b = self.find(b);
+        if a == b {
+            return false;
+        }
+        // hang the smaller tree below the bigger one, to keep paths short
+        let (big, small) = if self.sizes[a] >= self.sizes[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parents[small] = big;
+        self.sizes[big] += self.sizes[small];
+        self.component_count -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of indices in the component of the index.
+    pub fn size_of(&mut self, index: usize) -> usize {
+        let root = self.find(index);
+        self.sizes[root]
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.component_count
+    }
+
+    /// All components with their indices in ascending order.
+    /// Components are ordered by their smallest index.
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut components: Vec<Vec<usize>> = Vec::with_capacity(self.component_count);
+        // position of each root in the result
+        let mut positions: HashMap<usize, usize> = HashMap::new();
+        for index in 0..self.len() {
+            let root = self.find(index);
+            let position = *positions.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[position].push(index);
+        }
+        components
+    }
+
+    /// Sizes of all components, in the same order as [`DisjointSet::components`].
+    pub fn component_sizes(&mut self) -> Vec<usize> {
+        let mut seen = vec![false; self.len()];
+        let mut sizes = Vec::with_capacity(self.component_count);
+        for index in 0..self.len() {
+            let root = self.find(index);
+            if !seen[root] {
+                seen[root] = true;
+                sizes.push(self.sizes[root]);
+            }
+        }
+        sizes
+    }
+}
+
+/// [`DisjointSet`] over arbitrary keys. Keys get an index on first use.
+#[derive(Debug, Clone)]
+pub struct KeyedDisjointSet<K> {
+    set: DisjointSet,
+    indices: HashMap<K, usize>,
+    keys: Vec<K>,
+}
+
+impl<K: Hash + Eq + Clone> Default for KeyedDisjointSet<K> {
+    fn default() -> Self {
+        Self {
+            set: DisjointSet::default(),
+            indices: HashMap::new(),
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> KeyedDisjointSet<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Add the key in its own component, if it does not exist yet. Returns its index either way.
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(index) = self.indices.get(&key) {
+            return *index;
+        }
+        let index = self.set.add();
+        self.indices.insert(key.clone(), index);
+        self.keys.push(key);
+        index
+    }
+
+    pub fn index_of(&self, key: &K) -> Option<usize> {
+        self.indices.get(key).copied()
+    }
+
+    pub fn key(&self, index: usize) -> &K {
+        &self.keys[index]
+    }
+
+    /// Merge the components of both keys, inserting them if needed. Returns false, if they were already the same.
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let a = self.insert(a);
+        let b = self.insert(b);
+        self.set.union(a, b)
+    }
+
+    /// The representative key of the component, None for unknown keys.
+    pub fn find(&mut self, key: &K) -> Option<&K> {
+        let index = self.index_of(key)?;
+        let root = self.set.find(index);
+        Some(&self.keys[root])
+    }
+
+    /// Are both keys known and in the same component.
+    pub fn same(&mut self, a: &K, b: &K) -> bool {
+        match (self.index_of(a), self.index_of(b)) {
+            (Some(a), Some(b)) => self.set.same(a, b),
+            _ => false,
+        }
+    }
+
+    pub fn size_of(&mut self, key: &K) -> Option<usize> {
+        let index = self.index_of(key)?;
+        Some(self.set.size_of(index))
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.set.component_count()
+    }
+
+    /// All components, with keys in insertion order. Components are ordered by their first inserted key.
+    pub fn components(&mut self) -> Vec<Vec<K>> {
+        self.set
+            .components()
+            .into_iter()
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|index| self.keys[index].clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The underlying set, to work with indices directly.
+    pub fn set(&mut self) -> &mut DisjointSet {
+        &mut self.set
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Write},
+    hash::Hash,
+    path::Path,
+};
+
+use crate::graph::Graph;
+
+#[test]
+pub fn test_dot() {
+    let mut graph: Graph<&str> = Graph::new();
+    graph.add_edge("a", "b", 1);
+    graph.add_edge("b", "c", 2);
+    graph.add_edge("a", "c", 5);
+
+    let dot = Dot::new(&graph)
+        .name("test")
+        .label(|node| node.to_uppercase())
+        .weights()
+        .highlight_path(&["a", "b", "c"])
+        .cluster("start", ["a"])
+        .render();
+
+    assert!(dot.starts_with("digraph \"test\" {\n"));
+    assert!(
+        dot.contains("subgraph \"cluster_0\" {\n        label=\"start\";\n        0 [label=\"A\"")
+    );
+    assert!(dot.contains("    1 [label=\"B\", color=red];\n"));
+    assert!(dot.contains("    0 -> 1 [label=\"1\", color=red, penwidth=2];\n"));
+    assert!(dot.contains("    0 -> 2 [label=\"5\"];\n"));
+    assert!(dot.ends_with("}\n"));
+}
+
+/// Export of a [`Graph`] in the DOT language of graphviz, to look at it with e.g. `dot -Tsvg graph.dot > graph.svg`.
+/// Nodes are labelled with their debug output by default.
+pub struct Dot<'a, N> {
+    graph: &'a Graph<N>,
+    name: String,
+    label: Box<dyn Fn(&N) -> String + 'a>,
+    weights: bool,
+    highlighted_nodes: HashSet<usize>,
+    highlighted_edges: HashSet<(usize, usize)>,
+    // cluster of each node, as index into cluster_names
+    clusters: HashMap<usize, usize>,
+    cluster_names: Vec<String>,
+}
+
+impl<'a, N: Hash + Eq + Clone + Debug> Dot<'a, N> {
+    pub fn new(graph: &'a Graph<N>) -> Self {
+        Self {
+            graph,
+            name: "graph".to_string(),
+            label: Box::new(|node| format!("{:?}", node)),
+            weights: false,
+            highlighted_nodes: HashSet::new(),
+            highlighted_edges: HashSet::new(),
+            clusters: HashMap::new(),
+            cluster_names: Vec::new(),
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Custom node labels.
+    pub fn label(mut self, label: impl Fn(&N) -> String + 'a) -> Self {
+        self.label = Box::new(label);
+        self
+    }
+
+    /// Show the weight of every edge.
+    pub fn weights(mut self) -> Self {
+        self.weights = true;
+        self
+    }
+
+    /// Draw the nodes and the edges between them in red, e.g. the result of a search.
+    /// Nodes, that are not part of the graph, are ignored.
+    pub fn highlight_path(mut self, path: &[N]) -> Self {
+        let indices: Vec<Option<usize>> =
+            path.iter().map(|node| self.graph.index_of(node)).collect();
+        self.highlighted_nodes.extend(indices.iter().flatten());
+        indices.windows(2).for_each(|window| {
+            if let [Some(from), Some(to)] = window {
+                self.highlighted_edges.insert((*from, *to));
+            }
+        });
+        self
+    }
+
+    /// Group the nodes in a box with the given label. A node can only be in one cluster, the last one wins.
+    pub fn cluster(mut self, name: &str, nodes: impl IntoIterator<Item = N>) -> Self {
+        let cluster = self.cluster_names.len();
+        self.cluster_names.push(name.to_string());
+        nodes.into_iter().for_each(|node| {
+            if let Some(index) = self.graph.index_of(&node) {
+                self.clusters.insert(index, cluster);
+            }
+        });
+        self
+    }
+
+    /// Group all nodes by the name returned from the function. Nodes without a name are not clustered.
+    pub fn cluster_by(mut self, f: impl Fn(&N) -> Option<String>) -> Self {
+        let mut names: HashMap<String, usize> = HashMap::new();
+        self.graph
+            .nodes()
+            .iter()
+            .enumerate()
+            .for_each(|(index, node)| {
+                if let Some(name) = f(node) {
+                    let cluster = *names.entry(name.clone()).or_insert_with(|| {
+                        self.cluster_names.push(name);
+                        self.cluster_names.len() - 1
+                    });
+                    self.clusters.insert(index, cluster);
+                }
+            });
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let mut s = String::new();
+        writeln!(s, "digraph {} {{", quote(&self.name)).unwrap();
+
+        // clustered nodes have to be declared inside their subgraph
+        self.cluster_names
+            .iter()
+            .enumerate()
+            .for_each(|(cluster, name)| {
+                writeln!(
+                    s,
+                    "    subgraph {} {{",
+                    quote(&format!("cluster_{}", cluster))
+                )
+                .unwrap();
+                writeln!(s, "        label={};", quote(name)).unwrap();
+                (0..self.graph.len())
+                    .filter(|index| self.clusters.get(index) == Some(&cluster))
+                    .for_each(|index| self.write_node(&mut s, index, "        "));
+                writeln!(s, "    }}").unwrap();
+            });
+        (0..self.graph.len())
+            .filter(|index| !self.clusters.contains_key(index))
+            .for_each(|index| self.write_node(&mut s, index, "    "));
+
+        self.graph.edges().for_each(|(from, edge)| {
+            let mut attributes = Vec::new();
+            if self.weights {
+                attributes.push(format!("label={}", quote(&edge.weight.to_string())));
+            }
+            if self.highlighted_edges.contains(&(from, edge.to)) {
+                attributes.push("color=red, penwidth=2".to_string());
+            }
+            write!(s, "    {} -> {}", from, edge.to).unwrap();
+            if !attributes.is_empty() {
+                write!(s, " [{}]", attributes.join(", ")).unwrap();
+            }
+            writeln!(s, ";").unwrap();
+        });
+        writeln!(s, "}}").unwrap();
+        s
+    }
+
+    /// Render into a file, that can be passed to `dot`.
+    pub fn write(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.render())
+    }
+
+    fn write_node(&self, s: &mut String, index: usize, indent: &str) {
+        let label = (self.label)(self.graph.node(index));
+        write!(s, "{}{} [label={}", indent, index, quote(&label)).unwrap();
+        if self.highlighted_nodes.contains(&index) {
+            write!(s, ", color=red").unwrap();
+        }
+        writeln!(s, "];").unwrap();
+    }
+}
+
+/// DOT string literal, with quotes and backslashes escaped.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
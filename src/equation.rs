@@ -0,0 +1,228 @@
+use crate::digits;
+
+#[test]
+pub fn test_equation() {
+    let operators: [&dyn Operator; 2] = [&Add, &Mul];
+    assert_eq!(
+        solve(3267, &[81, 40, 27], &operators),
+        Some(Solutions {
+            count: 2,
+            example: "81 * 40 + 27".to_string()
+        })
+    );
+    assert!(is_solvable(190, &[10, 19], &operators));
+    assert!(!is_solvable(156, &[15, 6], &operators));
+    assert_eq!(solve(156, &[15, 6], &operators), None);
+
+    let operators: [&dyn Operator; 3] = [&Add, &Mul, &Concat];
+    let solutions = solve(7290, &[6, 8, 6, 15], &operators).unwrap();
+    assert_eq!(solutions.count, 1);
+    assert_eq!(solutions.example, "6 * 8 || 6 * 15");
+    assert_eq!(evaluate(&[6, 8, 6, 15], &[&Mul, &Concat, &Mul]), Some(7290));
+    assert_eq!(evaluate(&[u64::MAX, 2], &[&Mul]), None);
+
+    // multiplying with 0 at the end works for every choice before it
+    assert!(is_solvable(0, &[5, 0], &operators));
+    assert_eq!(solve(0, &[5, 3, 0], &operators).unwrap().count, 3);
+    // unless the values before overflow
+    assert!(!is_solvable(0, &[1, 1 << 40, 1 << 40, 0], &[&Mul]));
+    // 20 digits are only concatenated to 0
+    assert_eq!(
+        solve(u64::MAX, &[0, u64::MAX], &[&Concat]).unwrap().count,
+        1
+    );
+    assert_eq!(solve(u64::MAX, &[1, u64::MAX], &[&Concat]), None);
+}
+
+/// Binary operator of an equation, that is evaluated strictly left to right.
+pub trait Operator: Sync {
+    /// None if the result does not fit.
+    fn apply(&self, left: u64, right: u64) -> Option<u64>;
+    /// The left operand, so that left (op) right = result. None, if there is no such operand.
+    fn invert(&self, result: u64, right: u64) -> Option<Operand>;
+    fn symbol(&self) -> &str;
+}
+
+/// Left operand of an inverted operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Exactly(u64),
+    /// Every left operand gives the result, e.g. for 0 = left * 0.
+    Any,
+}
+
+pub struct Add;
+
+impl Operator for Add {
+    fn apply(&self, left: u64, right: u64) -> Option<u64> {
+        left.checked_add(right)
+    }
+
+    fn invert(&self, result: u64, right: u64) -> Option<Operand> {
+        result.checked_sub(right).map(Operand::Exactly)
+    }
+
+    fn symbol(&self) -> &str {
+        "+"
+    }
+}
+
+pub struct Mul;
+
+impl Operator for Mul {
+    fn apply(&self, left: u64, right: u64) -> Option<u64> {
+        left.checked_mul(right)
+    }
+
+    /// Multiplying with 0 loses the left operand, so any left operand works for a result of 0.
+    fn invert(&self, result: u64, right: u64) -> Option<Operand> {
+        if right == 0 {
+            return (result == 0).then_some(Operand::Any);
+        }
+        result
+            .is_multiple_of(right)
+            .then_some(Operand::Exactly(result / right))
+    }
+
+    fn symbol(&self) -> &str {
+        "*"
+    }
+}
+
+/// Append the digits of the right operand, e.g. 12 || 345 = 12345.
+pub struct Concat;
+
+impl Operator for Concat {
+    fn apply(&self, left: u64, right: u64) -> Option<u64> {
+        digits::checked_concat(left, right)
+    }
+
+    /// The result has to end with the digits of the right operand.
+    fn invert(&self, result: u64, right: u64) -> Option<Operand> {
+        let digits = digits::count(right);
+        // 10^20 does not fit into an u64, only 0 || right fits
+        if digits == 20 {
+            return (result == right).then_some(Operand::Exactly(0));
+        }
+        let shift = digits::pow10(digits);
+        (result % shift == right).then_some(Operand::Exactly(result / shift))
+    }
+
+    fn symbol(&self) -> &str {
+        "||"
+    }
+}
+
+/// All ways to place operators between the values, so they evaluate to the target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solutions {
+    /// Number of different operator choices.
+    pub count: u64,
+    /// The first solution that was found, e.g. "81 + 40 * 27".
+    pub example: String,
+}
+
+/// Count the operator choices, that turn the values into the target. None, if there is none.
+/// Searches backwards from the target, so every operator, that cannot be inverted for the last value,
+/// prunes all choices for the values before it.
+pub fn solve(target: u64, values: &[u64], operators: &[&dyn Operator]) -> Option<Solutions> {
+    let mut chosen = vec![0; values.len().saturating_sub(1)];
+    let mut example = None;
+    let count = search(
+        target,
+        values,
+        operators,
+        &mut chosen,
+        &mut |chosen: &[usize]| {
+            example.get_or_insert_with(|| format_expression(values, operators, chosen));
+            true
+        },
+    )?;
+    example.map(|example| Solutions { count, example })
+}
+
+/// Like [`solve`], but stops at the first solution.
+pub fn is_solvable(target: u64, values: &[u64], operators: &[&dyn Operator]) -> bool {
+    let mut chosen = vec![0; values.len().saturating_sub(1)];
+    search(target, values, operators, &mut chosen, &mut |_| false).is_none()
+}
+
+/// Evaluate the values with the operators between them, left to right. None, if any step does not fit.
+pub fn evaluate(values: &[u64], operators: &[&dyn Operator]) -> Option<u64> {
+    assert_eq!(
+        values.len(),
+        operators.len() + 1,
+        "There must be one operator between each pair of values."
+    );
+    let (first, rest) = values.split_first()?;
+    rest.iter()
+        .zip(operators)
+        .try_fold(*first, |result, (value, operator)| {
+            operator.apply(result, *value)
+        })
+}
+
+/// Number of solutions for the values, None if the search was stopped. The operators are chosen from the last value
+/// backwards, chosen holds the operator index for every position of the whole equation.
+/// found is called with the operator indices of every solution, and decides if the search goes on.
+fn search(
+    target: u64,
+    values: &[u64],
+    operators: &[&dyn Operator],
+    chosen: &mut [usize],
+    found: &mut impl FnMut(&[usize]) -> bool,
+) -> Option<u64> {
+    let Some((last, rest)) = values.split_last() else {
+        return Some(0);
+    };
+    if rest.is_empty() {
+        if target != *last {
+            return Some(0);
+        }
+        return found(chosen).then_some(1);
+    }
+
+    let mut count: u64 = 0;
+    for (index, operator) in operators.iter().enumerate() {
+        chosen[rest.len() - 1] = index;
+        count += match operator.invert(target, *last) {
+            None => continue,
+            Some(Operand::Exactly(left)) => search(left, rest, operators, chosen, found)?,
+            // the values before can evaluate to anything, as long as they fit
+            Some(Operand::Any) => search_forward(rest[0], &rest[1..], 0, operators, chosen, found)?,
+        };
+    }
+    Some(count)
+}
+
+/// Every operator choice for the values, that evaluates without overflow, counts as solution.
+/// left is the result of all values before, position is the index in chosen for the operator in front of values[0].
+fn search_forward(
+    left: u64,
+    values: &[u64],
+    position: usize,
+    operators: &[&dyn Operator],
+    chosen: &mut [usize],
+    found: &mut impl FnMut(&[usize]) -> bool,
+) -> Option<u64> {
+    let Some((next, rest)) = values.split_first() else {
+        return found(chosen).then_some(1);
+    };
+    let mut count: u64 = 0;
+    for (index, operator) in operators.iter().enumerate() {
+        let Some(result) = operator.apply(left, *next) else {
+            continue;
+        };
+        chosen[position] = index;
+        count += search_forward(result, rest, position + 1, operators, chosen, found)?;
+    }
+    Some(count)
+}
+
+fn format_expression(values: &[u64], operators: &[&dyn Operator], chosen: &[usize]) -> String {
+    let mut s = values[0].to_string();
+    values[1..].iter().zip(chosen).for_each(|(value, index)| {
+        s.push_str(&format!(" {} {}", operators[*index].symbol(), value));
+    });
+    s
+}
@@ -0,0 +1,226 @@
+use std::{fmt::Display, ops::Range};
+
+#[test]
+pub fn test_extract() {
+    assert_eq!(
+        signed("p=0,4 v=3,-3").collect::<Vec<i64>>(),
+        vec![0, 4, 3, -3]
+    );
+    assert_eq!(
+        unsigned("Button A: X+94, Y-34").collect::<Vec<u64>>(),
+        vec![94, 34]
+    );
+    // a minus between two numbers is a separator, not a sign
+    assert_eq!(signed("1-2 --3").collect::<Vec<i64>>(), vec![1, 2, -3]);
+    assert_eq!(signed("no numbers").next(), None);
+    assert_eq!(signed("-9223372036854775808").next(), Some(i64::MIN));
+
+    assert_eq!(signed_array::<2>("190: 10"), Ok([190, 10]));
+    assert_eq!(unsigned_array::<3>("1 2 3"), Ok([1, 2, 3]));
+    assert_eq!(
+        signed_array::<4>("p=0,4 v=3"),
+        Err(ExtractError::Count {
+            expected: 4,
+            found: 3,
+            input: "p=0,4 v=3".to_string()
+        })
+    );
+    assert_eq!(
+        signed_array::<1>("1 2").unwrap_err().to_string(),
+        "expected 1 integers, found 2 in \"1 2\""
+    );
+
+    // integers, that do not fit, are skipped by the iterators, but are an error for the arrays
+    let noisy = "x 123456789012345678901234 5";
+    assert_eq!(unsigned(noisy).collect::<Vec<u64>>(), vec![5]);
+    assert_eq!(
+        signed("-9223372036854775809 -1").collect::<Vec<i64>>(),
+        vec![-1]
+    );
+    assert_eq!(
+        unsigned_array::<2>(noisy),
+        Err(ExtractError::Overflow {
+            number: "123456789012345678901234".to_string(),
+            input: noisy.to_string()
+        })
+    );
+    assert_eq!(
+        signed_array::<1>("-9223372036854775809")
+            .unwrap_err()
+            .to_string(),
+        "-9223372036854775809 does not fit in \"-9223372036854775809\""
+    );
+}
+
+/// Why the integers of an input do not fit the expected array.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtractError {
+    /// The input contains a different count of integers than expected.
+    Count {
+        expected: usize,
+        found: usize,
+        input: String,
+    },
+    /// An integer is too large for the type.
+    Overflow { number: String, input: String },
+}
+
+impl Display for ExtractError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtractError::Count {
+                expected,
+                found,
+                input,
+            } => write!(
+                f,
+                "expected {} integers, found {} in {:?}",
+                expected, found, input
+            ),
+            ExtractError::Overflow { number, input } => {
+                write!(f, "{} does not fit in {:?}", number, input)
+            }
+        }
+    }
+}
+
+/// All integers in the text, everything else is skipped. A minus directly in front of the digits is a sign,
+/// unless there is a digit right before it, so "1-2" are two positive numbers.
+/// Integers, that do not fit into an i64, are skipped as well.
+pub fn signed(s: &str) -> Signed<'_> {
+    Signed {
+        bytes: s.as_bytes(),
+        position: 0,
+    }
+}
+
+/// All unsigned integers in the text, signs are skipped like any other character.
+/// Integers, that do not fit into an u64, are skipped as well.
+pub fn unsigned(s: &str) -> Unsigned<'_> {
+    Unsigned {
+        bytes: s.as_bytes(),
+        position: 0,
+    }
+}
+
+/// Exactly N signed integers, e.g. `let [px, py, vx, vy] = signed_array::<4>(line)?;`.
+pub fn signed_array<const N: usize>(s: &str) -> Result<[i64; N], ExtractError> {
+    let mut numbers = signed(s);
+    fill(|| numbers.next_checked(), s)
+}
+
+/// Exactly N unsigned integers.
+pub fn unsigned_array<const N: usize>(s: &str) -> Result<[u64; N], ExtractError> {
+    let mut numbers = unsigned(s);
+    fill(|| numbers.next_checked(), s)
+}
+
+/// next returns the byte range of an integer, that does not fit.
+fn fill<T: Default + Copy, const N: usize>(
+    mut next: impl FnMut() -> Option<Result<T, Range<usize>>>,
+    s: &str,
+) -> Result<[T; N], ExtractError> {
+    let mut array = [T::default(); N];
+    let mut found = 0;
+    while let Some(number) = next() {
+        let number = number.map_err(|range| ExtractError::Overflow {
+            number: s[range].to_string(),
+            input: s.to_string(),
+        })?;
+        // only count the rest, if there are too many
+        if found < N {
+            array[found] = number;
+        }
+        found += 1;
+    }
+    if found != N {
+        return Err(ExtractError::Count {
+            expected: N,
+            found,
+            input: s.to_string(),
+        });
+    }
+    Ok(array)
+}
+
+#[derive(Debug, Clone)]
+pub struct Signed<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Signed<'_> {
+    fn next_checked(&mut self) -> Option<Result<i64, Range<usize>>> {
+        let start = skip_to_digit(self.bytes, &mut self.position)?;
+        let is_negative = start > 0
+            && self.bytes[start - 1] == b'-'
+            && (start < 2 || !self.bytes[start - 2].is_ascii_digit());
+        let number = read_digits(self.bytes, &mut self.position).and_then(|magnitude| {
+            if is_negative {
+                0_i64.checked_sub_unsigned(magnitude)
+            } else {
+                i64::try_from(magnitude).ok()
+            }
+        });
+        let sign = if is_negative { 1 } else { 0 };
+        Some(number.ok_or(start - sign..self.position))
+    }
+}
+
+impl Iterator for Signed<'_> {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Ok(number) = self.next_checked()? {
+                return Some(number);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Unsigned<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Unsigned<'_> {
+    fn next_checked(&mut self) -> Option<Result<u64, Range<usize>>> {
+        let start = skip_to_digit(self.bytes, &mut self.position)?;
+        Some(read_digits(self.bytes, &mut self.position).ok_or(start..self.position))
+    }
+}
+
+impl Iterator for Unsigned<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Ok(number) = self.next_checked()? {
+                return Some(number);
+            }
+        }
+    }
+}
+
+/// Move to the next digit and return its position.
+fn skip_to_digit(bytes: &[u8], position: &mut usize) -> Option<usize> {
+    while *position < bytes.len() && !bytes[*position].is_ascii_digit() {
+        *position += 1;
+    }
+    (*position < bytes.len()).then_some(*position)
+}
+
+/// All digits from the position on, None if they do not fit into an u64.
+fn read_digits(bytes: &[u8], position: &mut usize) -> Option<u64> {
+    let mut number = Some(0_u64);
+    while *position < bytes.len() && bytes[*position].is_ascii_digit() {
+        let digit = (bytes[*position] - b'0') as u64;
+        number = number
+            .and_then(|number| number.checked_mul(10))
+            .and_then(|number| number.checked_add(digit));
+        *position += 1;
+    }
+    number
+}
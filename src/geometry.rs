@@ -0,0 +1,404 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::{math::gcd, scan::ScanField};
+
+#[test]
+pub fn test_line_points() {
+    let bounds = Bounds::from_size(10, 10);
+
+    // (2, 2) -> (6, 4) reduces to a step of (2, 1)
+    let points: Vec<Point> = Line::through(Point::new(2, 2), Point::new(6, 4))
+        .points_within(&bounds)
+        .collect();
+    assert_eq!(
+        points,
+        vec![
+            Point::new(0, 1),
+            Point::new(2, 2),
+            Point::new(4, 3),
+            Point::new(6, 4),
+            Point::new(8, 5)
+        ]
+    );
+
+    let points: Vec<Point> = Line::ray(Point::new(3, 3), Point::new(2, 2))
+        .points_within(&bounds)
+        .collect();
+    assert_eq!(
+        points,
+        vec![
+            Point::new(3, 3),
+            Point::new(2, 2),
+            Point::new(1, 1),
+            Point::new(0, 0)
+        ]
+    );
+
+    let points: Vec<Point> = Line::segment(Point::new(0, 0), Point::new(0, 6)).collect();
+    assert_eq!(points.len(), 7);
+
+    // vertical and horizontal lines must not divide by zero
+    let count = Line::through(Point::new(4, 1), Point::new(4, 2))
+        .points_within(&bounds)
+        .count();
+    assert_eq!(count, 10);
+
+    // the points given on creation do not need to be within the bounds
+    let points: Vec<Point> = Line::through(Point::new(-4, -2), Point::new(-2, -1))
+        .points_within(&bounds)
+        .collect();
+    assert_eq!(
+        points,
+        vec![
+            Point::new(0, 0),
+            Point::new(2, 1),
+            Point::new(4, 2),
+            Point::new(6, 3),
+            Point::new(8, 4)
+        ]
+    );
+    let ray = Line::ray(Point::new(12, 12), Point::new(11, 11));
+    assert_eq!(ray.points_within(&bounds).next(), Some(Point::new(9, 9)));
+    assert_eq!(ray.points_within(&bounds).count(), 10);
+    let away = Line::ray(Point::new(12, 12), Point::new(13, 13));
+    assert_eq!(away.points_within(&bounds).count(), 0);
+    let single = Line::through(Point::new(3, 4), Point::new(3, 4));
+    assert_eq!(
+        single.points_within(&bounds).collect::<Vec<_>>(),
+        vec![Point::new(3, 4)]
+    );
+}
+
+#[test]
+pub fn test_harmonic() {
+    let a = Point::new(4, 3);
+    let b = Point::new(5, 5);
+
+    assert_eq!(harmonic(a, b, 0), a);
+    assert_eq!(harmonic(a, b, 1), b);
+    assert_eq!(harmonic(a, b, -1), Point::new(3, 1));
+    assert_eq!(harmonic(a, b, 2), Point::new(6, 7));
+}
+
+/// A point on the integer lattice. x grows to the right, y grows downwards, like in the puzzle inputs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+impl Point {
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn new_usize(x: usize, y: usize) -> Self {
+        Self::new(x as i64, y as i64)
+    }
+
+    /// Divide both components by their gcd, so the result is the smallest lattice step in the same direction.
+    /// (0, 0) stays (0, 0).
+    pub fn reduced(&self) -> Point {
+        let divisor = gcd(self.x, self.y);
+        if divisor == 0 {
+            return *self;
+        }
+        Point::new(self.x / divisor, self.y / divisor)
+    }
+
+    /// Smallest lattice step from this point towards the other point.
+    pub fn step_towards(&self, other: &Point) -> Point {
+        (*other - *self).reduced()
+    }
+
+    /// Returns neighbors in North, East, South and West direction. x and y can be negative for edge cases.
+    pub fn neighbours_4(&self) -> [Point; 4] {
+        [
+            Point::new(self.x, self.y - 1),
+            Point::new(self.x + 1, self.y),
+            Point::new(self.x, self.y + 1),
+            Point::new(self.x - 1, self.y),
+        ]
+    }
+
+    pub fn manhattan_distance(&self, other: &Point) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Point) -> Point {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Point) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: i64) -> Point {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+/// "x,y", so points can be fields of scan templates.
+impl ScanField for Point {
+    fn scan_field(value: &str) -> Result<Self, String> {
+        let (x, y) = value
+            .split_once(',')
+            .ok_or_else(|| "expected x,y".to_string())?;
+        Ok(Point::new(i64::scan_field(x)?, i64::scan_field(y)?))
+    }
+}
+
+/// One of the four axis directions on the lattice. Up is negative y, like in the puzzle inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+impl Direction {
+    /// All directions, clockwise starting with Up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Index in clockwise order starting with Up, e.g. to store something per direction.
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        Direction::ALL[(self.index() + 1) % 4]
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        Direction::ALL[(self.index() + 3) % 4]
+    }
+
+    pub fn opposite(&self) -> Direction {
+        Direction::ALL[(self.index() + 2) % 4]
+    }
+
+    /// Vector of a single step in this direction.
+    pub fn step(&self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+        }
+    }
+}
+
+/// Axis aligned rectangle of lattice points. min is inclusive, max is exclusive,
+/// so a puzzle map of width x height is simply `Bounds::from_size(width, height)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+impl Bounds {
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_size(width: i64, height: i64) -> Self {
+        Self::new(Point::new(0, 0), Point::new(width, height))
+    }
+
+    pub fn width(&self) -> i64 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> i64 {
+        self.max.y - self.min.y
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        point.x >= self.min.x
+            && point.x < self.max.x
+            && point.y >= self.min.y
+            && point.y < self.max.y
+    }
+}
+
+/// Lattice points on a straight line, stepping by the gcd reduced vector between two points.
+/// Because of the reduction, no lattice point on the line is skipped, and no non-integer gradient checks are needed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    origin: Point,
+    step: Point,
+    kind: LineKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LineKind {
+    /// Infinite in both directions.
+    Full,
+    /// Starts at origin, infinite in direction of step.
+    Ray,
+}
+
+impl Line {
+    /// Infinite line through both points.
+    pub fn through(a: Point, b: Point) -> Self {
+        Self {
+            origin: a,
+            step: a.step_towards(&b),
+            kind: LineKind::Full,
+        }
+    }
+
+    /// Half infinite line starting at a, going through b.
+    pub fn ray(a: Point, b: Point) -> Self {
+        Self {
+            origin: a,
+            step: a.step_towards(&b),
+            kind: LineKind::Ray,
+        }
+    }
+
+    /// All lattice points from a to b, both inclusive.
+    pub fn segment(a: Point, b: Point) -> LinePoints {
+        let step = a.step_towards(&b);
+        let delta = b - a;
+        // both components are multiples of the step, pick the one that is not 0
+        let steps = if step.x != 0 {
+            delta.x / step.x
+        } else if step.y != 0 {
+            delta.y / step.y
+        } else {
+            0
+        };
+        LinePoints {
+            next: a,
+            step,
+            remaining: steps + 1,
+        }
+    }
+
+    pub fn step(&self) -> Point {
+        self.step
+    }
+
+    /// All lattice points of the line, that lie within the bounds. Ordered in direction of the step.
+    /// The line is clipped to the bounds first, so the points given on creation may lie outside of them.
+    pub fn points_within(&self, bounds: &Bounds) -> LinePoints {
+        let nothing = LinePoints {
+            next: self.origin,
+            step: self.step,
+            remaining: 0,
+        };
+        // the line is origin + t * step, look for the lowest and highest t within the bounds
+        let mut lowest = match self.kind {
+            LineKind::Full => i64::MIN,
+            LineKind::Ray => 0,
+        };
+        let mut highest = i64::MAX;
+        for (from, step, min, max) in [
+            (self.origin.x, self.step.x, bounds.min.x, bounds.max.x),
+            (self.origin.y, self.step.y, bounds.min.y, bounds.max.y),
+        ] {
+            if step == 0 {
+                // parallel to this axis, it is within the bounds everywhere or nowhere
+                if from < min || from >= max {
+                    return nothing;
+                }
+                continue;
+            }
+            // min <= from + t * step < max, dividing by a negative step swaps both sides
+            let (low, high) = if step > 0 {
+                (min - from, max - 1 - from)
+            } else {
+                (max - 1 - from, min - from)
+            };
+            lowest = lowest.max(div_ceil(low, step));
+            highest = highest.min(div_floor(high, step));
+        }
+        if self.step == Point::new(0, 0) {
+            // both points were the same, the line is only that point
+            (lowest, highest) = (0, 0);
+        }
+        if lowest > highest {
+            return nothing;
+        }
+
+        LinePoints {
+            next: self.origin + self.step * lowest,
+            step: self.step,
+            remaining: highest - lowest + 1,
+        }
+    }
+}
+
+/// Iterator over the lattice points of a [`Line`].
+#[derive(Debug, Clone)]
+pub struct LinePoints {
+    next: Point,
+    step: Point,
+    remaining: i64,
+}
+impl Iterator for LinePoints {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        if self.remaining <= 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let current = self.next;
+        // no step after the last point, it could leave the range of i64
+        if self.remaining > 0 {
+            self.next = current + self.step;
+        }
+        Some(current)
+    }
+}
+
+fn div_floor(a: i64, b: i64) -> i64 {
+    if b < 0 {
+        (-a).div_euclid(-b)
+    } else {
+        a.div_euclid(b)
+    }
+}
+
+fn div_ceil(a: i64, b: i64) -> i64 {
+    -div_floor(-a, b)
+}
+
+/// The k-th harmonic of two points: a + k * (b - a), without gcd reduction.
+/// k = 0 is a, k = 1 is b, k = -1 and k = 2 are the mirrored points on both sides.
+pub fn harmonic(a: Point, b: Point, k: i64) -> Point {
+    a + (b - a) * k
+}
@@ -0,0 +1,279 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::search::{Control, Search};
+
+#[test]
+pub fn test_graph() {
+    let mut graph: Graph<&str> = Graph::new();
+    graph.add_edge("a", "b", 1);
+    graph.add_edge("a", "c", 5);
+    graph.add_edge("c", "b", 2);
+
+    assert_eq!(graph.len(), 3);
+    assert_eq!(graph.edge_count(), 3);
+    let b = graph.index_of(&"b").unwrap();
+    assert_eq!(graph.node(b), &"b");
+    assert_eq!(graph.predecessors(b).len(), 2);
+    assert_eq!(graph.edge_weight(&"c", &"b"), Some(2));
+    assert_eq!(graph.edge_weight(&"b", &"c"), None);
+    assert_eq!(
+        graph.successor_nodes(&"a").collect::<Vec<&&str>>(),
+        vec![&"b", &"c"]
+    );
+}
+
+#[test]
+pub fn test_compress_corridors() {
+    let grid = crate::grid::Grid::parse(".....\n.#.#.\n.....", |ch| ch);
+    let graph = grid.to_weighted_graph(|ch| *ch == '.', |_, _| Some(1));
+    let start = crate::geometry::Point::new(0, 0);
+    let end = crate::geometry::Point::new(4, 2);
+
+    // only the two crossings in the middle are junctions, start and end are kept explicitly
+    let compressed = graph.compress_corridors(|point| *point == start || *point == end);
+    assert_eq!(compressed.len(), 4);
+    assert_eq!(compressed.edge_count(), 10);
+
+    for graph in [&graph, &compressed] {
+        let (length, path) = graph.shortest_path(&start, &end).unwrap();
+        assert_eq!(length, 6);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        assert_eq!(graph.longest_path(&start, &end), Some(10));
+    }
+}
+
+/// Directed edge to the node with the given index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub to: usize,
+    pub weight: u64,
+}
+
+/// Directed graph with weighted edges. Every node value exists only once and gets a stable index,
+/// so algorithms can work with indices while callers work with their own node type.
+/// Undirected graphs simply add both directions.
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    successors: Vec<Vec<Edge>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl<N: Hash + Eq + Clone> Default for Graph<N> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+            successors: Vec::new(),
+            predecessors: Vec::new(),
+        }
+    }
+}
+
+impl<N: Hash + Eq + Clone> Graph<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the node, if it does not exist yet. Returns its index either way.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(index) = self.indices.get(&node) {
+            return *index;
+        }
+        let index = self.nodes.len();
+        self.indices.insert(node.clone(), index);
+        self.nodes.push(node);
+        self.successors.push(Vec::new());
+        self.predecessors.push(Vec::new());
+        index
+    }
+
+    /// Add an edge, and both nodes if needed.
+    pub fn add_edge(&mut self, from: N, to: N, weight: u64) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.add_edge_by_index(from, to, weight);
+    }
+
+    pub fn add_edge_by_index(&mut self, from: usize, to: usize, weight: u64) {
+        self.successors[from].push(Edge { to, weight });
+        self.predecessors[to].push(from);
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.successors.iter().map(|edges| edges.len()).sum()
+    }
+
+    pub fn index_of(&self, node: &N) -> Option<usize> {
+        self.indices.get(node).copied()
+    }
+
+    pub fn node(&self, index: usize) -> &N {
+        &self.nodes[index]
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// Outgoing edges of the node with the given index.
+    pub fn successors(&self, index: usize) -> &[Edge] {
+        &self.successors[index]
+    }
+
+    /// Indices of all nodes with an edge to the node with the given index.
+    pub fn predecessors(&self, index: usize) -> &[usize] {
+        &self.predecessors[index]
+    }
+
+    /// Successor node values, e.g. as neighbour function for the search module.
+    pub fn successor_nodes(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.index_of(node)
+            .map(|index| self.successors[index].as_slice())
+            .unwrap_or(&[])
+            .iter()
+            .map(|edge| &self.nodes[edge.to])
+    }
+
+    /// Successor node values with the edge weights, e.g. as neighbour function for dijkstra.
+    pub fn weighted_successor_nodes(&self, node: &N) -> impl Iterator<Item = (&N, u64)> {
+        self.index_of(node)
+            .map(|index| self.successors[index].as_slice())
+            .unwrap_or(&[])
+            .iter()
+            .map(|edge| (&self.nodes[edge.to], edge.weight))
+    }
+
+    /// Weight of the first edge between both nodes, if any.
+    pub fn edge_weight(&self, from: &N, to: &N) -> Option<u64> {
+        let from = self.index_of(from)?;
+        let to = self.index_of(to)?;
+        self.successors[from]
+            .iter()
+            .find(|edge| edge.to == to)
+            .map(|edge| edge.weight)
+    }
+
+    /// All edges as (from index, edge).
+    pub fn edges(&self) -> impl Iterator<Item = (usize, &Edge)> {
+        self.successors
+            .iter()
+            .enumerate()
+            .flat_map(|(from, edges)| edges.iter().map(move |edge| (from, edge)))
+    }
+
+    /// A node, that is only passed through: either exactly one edge in and a different one out,
+    /// or connected in both directions to exactly two different neighbours.
+    pub fn is_corridor(&self, index: usize) -> bool {
+        match (
+            self.predecessors[index].as_slice(),
+            self.successors[index].as_slice(),
+        ) {
+            ([before], [after]) => *before != after.to,
+            ([a, b], [c, d]) => {
+                a != b && ((*a == c.to && *b == d.to) || (*a == d.to && *b == c.to))
+            }
+            _ => false,
+        }
+    }
+
+    /// Collapse chains of corridor nodes into single edges between the remaining nodes, with the summed weights.
+    /// Nodes for which keep returns true are never removed, e.g. start and goal.
+    /// Paths between the remaining nodes keep their lengths, parallel corridors become parallel edges.
+    /// Rings, that consist only of corridors, are dropped, because they cannot be reached from anywhere else.
+    pub fn compress_corridors(&self, mut keep: impl FnMut(&N) -> bool) -> Graph<N> {
+        let corridor: Vec<bool> = (0..self.len())
+            .map(|index| !keep(&self.nodes[index]) && self.is_corridor(index))
+            .collect();
+
+        let mut compressed = Graph::new();
+        for from in (0..self.len()).filter(|index| !corridor[*index]) {
+            let compressed_from = compressed.add_node(self.nodes[from].clone());
+            for edge in self.successors[from].iter() {
+                // follow the corridor until the next junction, never going back
+                let mut weight = edge.weight;
+                let mut previous = from;
+                let mut current = edge.to;
+                while corridor[current] {
+                    let next = self.successors[current]
+                        .iter()
+                        .find(|next| next.to != previous)
+                        .expect("Corridors always have a way forward.");
+                    weight += next.weight;
+                    previous = current;
+                    current = next.to;
+                }
+                let compressed_to = compressed.add_node(self.nodes[current].clone());
+                compressed.add_edge_by_index(compressed_from, compressed_to, weight);
+            }
+        }
+        compressed
+    }
+
+    /// Cheapest path with its total weight, using dijkstra.
+    pub fn shortest_path(&self, from: &N, to: &N) -> Option<(u64, Vec<N>)> {
+        let from = self.index_of(from)?;
+        let to = self.index_of(to)?;
+        let result = Search::new().with_paths().dijkstra(
+            [from],
+            |index| {
+                self.successors[*index]
+                    .iter()
+                    .map(|edge| (edge.to, edge.weight))
+            },
+            |index, _| Control::stop_if(*index == to),
+        );
+        let cost = result.cost?;
+        let path = result.into_path()?;
+        Some((
+            cost,
+            path.into_iter()
+                .map(|index| self.nodes[index].clone())
+                .collect(),
+        ))
+    }
+
+    /// Total weight of the longest path, that visits no node twice. Tries every path, so only use it on small graphs,
+    /// e.g. after [`Graph::compress_corridors`].
+    pub fn longest_path(&self, from: &N, to: &N) -> Option<u64> {
+        let from = self.index_of(from)?;
+        let to = self.index_of(to)?;
+        let mut on_path = vec![false; self.len()];
+        let mut longest = None;
+
+        // every entry is a node, the position of the next edge to check and the length up to the node
+        let mut path: Vec<(usize, usize, u64)> = vec![(from, 0, 0)];
+        on_path[from] = true;
+        while let Some((node, edge_position, length)) = path.last_mut() {
+            let node = *node;
+            let length = *length;
+            let next = if node == to {
+                longest = longest.max(Some(length));
+                None
+            } else {
+                self.successors[node].get(*edge_position)
+            };
+            let Some(edge) = next else {
+                on_path[node] = false;
+                path.pop();
+                continue;
+            };
+            *edge_position += 1;
+            if !on_path[edge.to] {
+                on_path[edge.to] = true;
+                path.push((edge.to, 0, length + edge.weight));
+            }
+        }
+        longest
+    }
+}
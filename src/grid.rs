@@ -0,0 +1,301 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    disjoint_set::DisjointSet,
+    geometry::{Bounds, Point},
+    graph::Graph,
+};
+
+pub mod bit_grid;
+pub mod byte_grid;
+pub mod history;
+pub mod sparse_grid;
+pub mod wrapping_grid;
+
+pub use bit_grid::{BitGrid, BitGrid4};
+pub use byte_grid::ByteGrid;
+pub use history::{CellChange, History, Replay};
+pub use sparse_grid::SparseGrid;
+pub use wrapping_grid::WrappingGrid;
+
+#[test]
+pub fn test_grid() {
+    let mut grid = Grid::parse("abc\ndef\n", |ch| ch);
+
+    assert_eq!(grid.width(), 3);
+    assert_eq!(grid.height(), 2);
+    assert_eq!(grid.get(Point::new(1, 1)), Some(&'e'));
+    assert_eq!(grid.get(Point::new(3, 1)), None);
+    assert_eq!(grid.get(Point::new(-1, 0)), None);
+    assert_eq!(grid.find(|ch| *ch == 'f'), Some(Point::new(2, 1)));
+
+    assert_eq!(grid.set(Point::new(0, 0), 'x'), Some('a'));
+    assert_eq!(grid.neighbours_4(Point::new(0, 0)).count(), 2);
+    assert_eq!(grid.to_string(), "xbc\ndef\n");
+
+    let grid = Grid::parse("aab\nbab\n", |ch| ch);
+    let components = grid.connected_components(|a, b| a == b);
+    assert_eq!(components.len(), 3);
+    assert_eq!(
+        components[0],
+        vec![Point::new(0, 0), Point::new(1, 0), Point::new(1, 1)]
+    );
+    assert_eq!(components[1], vec![Point::new(2, 0), Point::new(2, 1)]);
+
+    // only downhill edges
+    let graph = grid.to_graph(|from, to| from > to);
+    assert_eq!(graph.len(), 6);
+    assert_eq!(graph.edge_count(), 4);
+}
+
+/// Dense, rectangular grid with (0, 0) in the top left corner.
+/// Cells are stored row by row in one continuous vec.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Create a grid from lines of characters. Empty lines are skipped, so trailing newlines do not create an empty row.
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> T) -> Self {
+        let rows: Vec<Vec<T>> = crate::input::lines(input)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().map(&mut f).collect())
+            .collect();
+        Self::from_rows(rows)
+    }
+
+    /// Cells row by row, there must be exactly width * height of them.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "A grid needs exactly width * height cells."
+        );
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// All rows must have the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "All rows of a grid must have the same length."
+        );
+
+        Self {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds::from_size(self.width as i64, self.height as i64)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.index_of(point).is_some()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|index| &mut self.cells[index])
+    }
+
+    /// Replace the value at the point and return the old one. Out of bounds points are ignored and return None.
+    pub fn set(&mut self, point: Point, value: T) -> Option<T> {
+        self.get_mut(point)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// All cells with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(index, value)| (Point::new_usize(index % width, index / width), value))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |index| Point::new_usize(index % width, index / width))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on 0, an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Position of the first cell (row by row) that matches.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter()
+            .find(|(_, value)| predicate(value))
+            .map(|(point, _)| point)
+    }
+
+    /// The up to 4 neighbours in North, East, South and West direction, that are within the grid.
+    pub fn neighbours_4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point
+            .neighbours_4()
+            .into_iter()
+            .filter(|neighbour| self.contains(*neighbour))
+    }
+
+    /// Groups of cells, that are connected in North, East, South or West direction,
+    /// where each pair of neighbours is the same according to the function.
+    /// Components are ordered by their first cell and their cells are row by row.
+    pub fn connected_components(&self, mut same: impl FnMut(&T, &T) -> bool) -> Vec<Vec<Point>> {
+        connected_components(self.width, self.height, |a, b| {
+            same(&self.cells[a], &self.cells[b])
+        })
+    }
+
+    /// Graph with every cell as node, and an edge to each neighbour in North, East, South and West direction,
+    /// if the function allows moving from the one cell to the other. Nodes are added row by row.
+    pub fn to_graph(&self, mut connected: impl FnMut(&T, &T) -> bool) -> Graph<Point> {
+        let mut graph = Graph::new();
+        self.positions().for_each(|point| {
+            graph.add_node(point);
+        });
+        self.iter().for_each(|(point, value)| {
+            self.neighbours_4(point).for_each(|neighbour| {
+                if connected(value, &self[neighbour]) {
+                    graph.add_edge(point, neighbour, 1);
+                }
+            });
+        });
+        graph
+    }
+
+    /// Graph of all passable cells, with an edge to each passable neighbour in North, East, South and West direction.
+    /// The cost function gets the value of the cell and the neighbour, and returns the weight of the edge,
+    /// or None if the neighbour cannot be entered from there.
+    pub fn to_weighted_graph(
+        &self,
+        mut passable: impl FnMut(&T) -> bool,
+        mut cost: impl FnMut(&T, &T) -> Option<u64>,
+    ) -> Graph<Point> {
+        let mut graph = Graph::new();
+        for (point, value) in self.iter() {
+            if !passable(value) {
+                continue;
+            }
+            let from = graph.add_node(point);
+            for neighbour in self.neighbours_4(point) {
+                let neighbour_value = &self[neighbour];
+                if !passable(neighbour_value) {
+                    continue;
+                }
+                if let Some(weight) = cost(value, neighbour_value) {
+                    let to = graph.add_node(neighbour);
+                    graph.add_edge_by_index(from, to, weight);
+                }
+            }
+        }
+        graph
+    }
+
+    /// Render every cell with the given function, one line per row.
+    pub fn render<D: Display>(&self, f: impl Fn(&T) -> D) -> String {
+        let mut s = String::new();
+        for row in self.rows() {
+            for value in row {
+                s.push_str(&f(value).to_string());
+            }
+            s.push('\n');
+        }
+        s
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        if point.x < 0
+            || point.y < 0
+            || point.x >= self.width as i64
+            || point.y >= self.height as i64
+        {
+            return None;
+        }
+        Some(point.y as usize * self.width + point.x as usize)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point).expect("Point is out of bounds.")
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point).expect("Point is out of bounds.")
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(|value| value.to_string()))
+    }
+}
+
+/// Connected components of a grid, where same compares two cells by their row-major index.
+pub(crate) fn connected_components(
+    width: usize,
+    height: usize,
+    mut same: impl FnMut(usize, usize) -> bool,
+) -> Vec<Vec<Point>> {
+    let len = width * height;
+    let mut set = DisjointSet::new(len);
+    for index in 0..len {
+        // right and down are enough, left and up were already checked by the other cell
+        if index % width + 1 < width && same(index, index + 1) {
+            set.union(index, index + 1);
+        }
+        if index + width < len && same(index, index + width) {
+            set.union(index, index + width);
+        }
+    }
+    set.components()
+        .into_iter()
+        .map(|component| {
+            component
+                .into_iter()
+                .map(|index| Point::new_usize(index % width, index / width))
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,284 @@
+use crate::geometry::{Bounds, Direction, Point};
+
+use super::Grid;
+
+#[test]
+pub fn test_bit_grid() {
+    let mut visited = BitGrid::new(70, 3);
+    assert!(visited.set(Point::new(0, 0)));
+    assert!(!visited.set(Point::new(0, 0)));
+    assert!(visited.set(Point::new(69, 2)));
+    // out of bounds is ignored
+    assert!(!visited.set(Point::new(70, 0)));
+    assert!(!visited.get(Point::new(-1, 0)));
+    assert_eq!(visited.count(), 2);
+
+    let mut other = BitGrid::new(70, 3);
+    other.set(Point::new(69, 2));
+    other.set(Point::new(5, 1));
+    assert_eq!(visited.union(&other).count(), 3);
+    assert_eq!(
+        visited.intersection(&other).iter().collect::<Vec<Point>>(),
+        vec![Point::new(69, 2)]
+    );
+
+    assert!(visited.unset(Point::new(0, 0)));
+    assert_eq!(visited.count(), 1);
+}
+
+#[test]
+pub fn test_bit_grid_4() {
+    let mut history = BitGrid4::new(5, 5);
+    assert!(history.set(Point::new(1, 1), Direction::Up));
+    assert!(history.set(Point::new(1, 1), Direction::Left));
+    assert!(!history.set(Point::new(1, 1), Direction::Up));
+    assert!(!history.get(Point::new(1, 1), Direction::Down));
+    assert!(history.get_any(Point::new(1, 1)));
+    assert_eq!(history.count(), 2);
+    assert_eq!(history.cells().count(), 1);
+}
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// One bit per cell, packed into u64 words. Useful to track visited or occupied fields of a grid.
+/// Points out of bounds are never set.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            words: vec![0; (width * height).div_ceil(WORD_BITS)],
+        }
+    }
+
+    /// Set every cell of the grid, that matches the predicate.
+    pub fn from_grid<T>(grid: &Grid<T>, mut predicate: impl FnMut(&T) -> bool) -> Self {
+        let mut bits = Self::new(grid.width(), grid.height());
+        grid.iter()
+            .filter(|(_, value)| predicate(value))
+            .for_each(|(point, _)| {
+                bits.set(point);
+            });
+        bits
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds::from_size(self.width as i64, self.height as i64)
+    }
+
+    pub fn get(&self, point: Point) -> bool {
+        self.index_of(point)
+            .map(|index| self.words[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0)
+            .unwrap_or(false)
+    }
+
+    /// Set the bit. Returns true, if it was not set before.
+    pub fn set(&mut self, point: Point) -> bool {
+        let Some(index) = self.index_of(point) else {
+            return false;
+        };
+        let word = &mut self.words[index / WORD_BITS];
+        let mask = 1 << (index % WORD_BITS);
+        let was_set = *word & mask != 0;
+        *word |= mask;
+        !was_set
+    }
+
+    /// Clear the bit. Returns true, if it was set before.
+    pub fn unset(&mut self, point: Point) -> bool {
+        let Some(index) = self.index_of(point) else {
+            return false;
+        };
+        let word = &mut self.words[index / WORD_BITS];
+        let mask = 1 << (index % WORD_BITS);
+        let was_set = *word & mask != 0;
+        *word &= !mask;
+        was_set
+    }
+
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// Number of set bits.
+    pub fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Set all bits, that are set in the other grid. Both grids must have the same size.
+    pub fn union_with(&mut self, other: &BitGrid) {
+        self.combine_with(other, |a, b| a | b);
+    }
+
+    /// Keep only bits, that are set in the other grid as well. Both grids must have the same size.
+    pub fn intersect_with(&mut self, other: &BitGrid) {
+        self.combine_with(other, |a, b| a & b);
+    }
+
+    /// Clear all bits, that are set in the other grid. Both grids must have the same size.
+    pub fn difference_with(&mut self, other: &BitGrid) {
+        self.combine_with(other, |a, b| a & !b);
+    }
+
+    pub fn union(&self, other: &BitGrid) -> BitGrid {
+        let mut result = self.clone();
+        result.union_with(other);
+        result
+    }
+
+    pub fn intersection(&self, other: &BitGrid) -> BitGrid {
+        let mut result = self.clone();
+        result.intersect_with(other);
+        result
+    }
+
+    /// All set points, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        let width = self.width;
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(word_index, word)| {
+                // walk over the set bits only, by clearing the lowest one after each step
+                let mut word = *word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some(word_index * WORD_BITS + bit)
+                })
+            })
+            .map(move |index| Point::new_usize(index % width, index / width))
+    }
+
+    fn combine_with(&mut self, other: &BitGrid, f: impl Fn(u64, u64) -> u64) {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "Bit grids must have the same size to be combined."
+        );
+        self.words
+            .iter_mut()
+            .zip(other.words.iter())
+            .for_each(|(word, other_word)| *word = f(*word, *other_word));
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        if point.x < 0
+            || point.y < 0
+            || point.x >= self.width as i64
+            || point.y >= self.height as i64
+        {
+            return None;
+        }
+        Some(point.y as usize * self.width + point.x as usize)
+    }
+}
+
+/// One bit per cell and direction. Tracks e.g. in which directions a walker has passed a field,
+/// which is enough to detect that it walks in a loop.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid4 {
+    // the 4 direction bits of a cell are next to each other in the same row
+    bits: BitGrid,
+}
+
+impl BitGrid4 {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            bits: BitGrid::new(width * 4, height),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.bits.width() / 4
+    }
+
+    pub fn height(&self) -> usize {
+        self.bits.height()
+    }
+
+    pub fn get(&self, point: Point, direction: Direction) -> bool {
+        self.bit_point(point, direction)
+            .map(|bit_point| self.bits.get(bit_point))
+            .unwrap_or(false)
+    }
+
+    /// Is the bit of any direction set
+    pub fn get_any(&self, point: Point) -> bool {
+        Direction::ALL
+            .iter()
+            .any(|direction| self.get(point, *direction))
+    }
+
+    /// Set the bit. Returns true, if it was not set before.
+    pub fn set(&mut self, point: Point, direction: Direction) -> bool {
+        self.bit_point(point, direction)
+            .map(|bit_point| self.bits.set(bit_point))
+            .unwrap_or(false)
+    }
+
+    /// Clear the bit. Returns true, if it was set before.
+    pub fn unset(&mut self, point: Point, direction: Direction) -> bool {
+        self.bit_point(point, direction)
+            .map(|bit_point| self.bits.unset(bit_point))
+            .unwrap_or(false)
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    /// Number of set bits over all directions.
+    pub fn count(&self) -> usize {
+        self.bits.count()
+    }
+
+    pub fn union_with(&mut self, other: &BitGrid4) {
+        self.bits.union_with(&other.bits);
+    }
+
+    pub fn intersect_with(&mut self, other: &BitGrid4) {
+        self.bits.intersect_with(&other.bits);
+    }
+
+    /// All cells, that have the bit of any direction set.
+    pub fn cells(&self) -> BitGrid {
+        let mut cells = BitGrid::new(self.width(), self.height());
+        self.bits.iter().for_each(|bit_point| {
+            cells.set(Point::new(bit_point.x / 4, bit_point.y));
+        });
+        cells
+    }
+
+    fn bit_point(&self, point: Point, direction: Direction) -> Option<Point> {
+        if point.x < 0 || point.x >= self.width() as i64 {
+            // would spill into the neighbouring cell
+            return None;
+        }
+        Some(Point::new(point.x * 4 + direction.index() as i64, point.y))
+    }
+}
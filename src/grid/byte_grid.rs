@@ -0,0 +1,286 @@
+use std::ops::Index;
+
+use crate::geometry::{Bounds, Direction, Point};
+
+use super::Grid;
+
+#[test]
+pub fn test_byte_grid() {
+    let grid = ByteGrid::new("ab.\nd.f\n");
+    assert_eq!(grid.width(), 3);
+    assert_eq!(grid.height(), 2);
+    assert_eq!(grid.get(Point::new(1, 1)), Some(b'.'));
+    assert_eq!(grid[Point::new(2, 1)], b'f');
+    assert_eq!(grid.get(Point::new(3, 0)), None);
+    assert_eq!(grid.get(Point::new(0, -1)), None);
+    assert_eq!(grid.row(1), b"d.f");
+    assert_eq!(grid.find(b'f'), Some(Point::new(2, 1)));
+    assert_eq!(
+        grid.positions_of(b'.').collect::<Vec<Point>>(),
+        vec![Point::new(2, 0), Point::new(1, 1)]
+    );
+    assert_eq!(grid.neighbours_4(Point::new(0, 0)).count(), 2);
+    assert_eq!(grid.to_grid(|byte| byte as char).to_string(), "ab.\nd.f\n");
+
+    // index based access, steps over the border hit the line endings
+    let index = grid.index_of(Point::new(2, 0)).unwrap();
+    assert_eq!(grid.point_of(index), Point::new(2, 0));
+    assert_eq!(grid.byte(index), b'.');
+    assert_eq!(grid.step(index, Point::new(1, 0)), None);
+    assert_eq!(grid.step(index, Point::new(0, -1)), None);
+    assert_eq!(
+        grid.step(index, Point::new(-1, 1)),
+        grid.index_of(Point::new(1, 1))
+    );
+    assert_eq!(
+        grid.step(grid.index_of(Point::new(0, 1)).unwrap(), Point::new(-1, 0)),
+        None
+    );
+    assert_eq!(
+        grid.step(grid.index_of(Point::new(2, 1)).unwrap(), Point::new(1, 1)),
+        None
+    );
+    assert_eq!(
+        grid.indices_of(b'.')
+            .map(|index| grid.point_of(index))
+            .collect::<Vec<Point>>(),
+        vec![Point::new(2, 0), Point::new(1, 1)]
+    );
+    assert_eq!(grid.indices_of(b'\n').count(), 0);
+    assert_eq!(
+        grid.neighbours_4_of_index(grid.index_of(Point::new(1, 1)).unwrap())
+            .map(|index| grid.point_of(index))
+            .collect::<Vec<Point>>(),
+        vec![Point::new(1, 0), Point::new(2, 1), Point::new(0, 1)]
+    );
+
+    let components = ByteGrid::new("aab\r\nbab\r\n").connected_components(|a, b| a == b);
+    assert_eq!(components.len(), 3);
+    assert_eq!(
+        components[0],
+        vec![Point::new(0, 0), Point::new(1, 0), Point::new(1, 1)]
+    );
+
+    // same view for CRLF and without trailing newline
+    let crlf = ByteGrid::new("ab.\r\nd.f");
+    assert_eq!(crlf.width(), 3);
+    assert_eq!(crlf.row(1), b"d.f");
+    assert_eq!(
+        crlf.iter().collect::<Vec<_>>(),
+        grid.iter().collect::<Vec<_>>()
+    );
+
+    let blank_lines = ByteGrid::new("ab.\nd.f\n\n\r\n");
+    assert_eq!(blank_lines.height(), 2);
+    assert_eq!(blank_lines.row(1), b"d.f");
+    assert_eq!(ByteGrid::new("abc").height(), 1);
+    assert_eq!(ByteGrid::new("").height(), 0);
+}
+
+/// Read only grid view over the bytes of the input, without copying anything.
+/// Rows are found by a stride (width plus the line ending), so there is no allocation per row.
+/// Only works for ascii inputs, all rows must have the same length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ByteGrid<'a> {
+    bytes: &'a [u8],
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl<'a> ByteGrid<'a> {
+    /// Lines may end with LF or CRLF, trailing line endings and empty lines are ignored. Panics on rows of different length.
+    pub fn new(input: &'a str) -> Self {
+        let bytes = input.as_bytes();
+        let bytes = bytes.strip_prefix("\u{feff}".as_bytes()).unwrap_or(bytes);
+        // empty lines at the end are no rows, like in Grid::parse
+        let end = bytes
+            .iter()
+            .rposition(|byte| *byte != b'\n' && *byte != b'\r')
+            .map_or(0, |last| last + 1);
+        let bytes = &bytes[..end];
+        let (width, stride) = match bytes.iter().position(|byte| *byte == b'\n') {
+            Some(newline) if newline > 0 && bytes[newline - 1] == b'\r' => {
+                (newline - 1, newline + 1)
+            }
+            Some(newline) => (newline, newline + 1),
+            None => (bytes.len(), bytes.len() + 1),
+        };
+        let line_ending = stride - width;
+        // the last row may come without line ending
+        let height = if bytes.is_empty() {
+            0
+        } else {
+            (bytes.len() + line_ending) / stride
+        };
+        let grid = Self {
+            bytes,
+            width,
+            height,
+            stride,
+        };
+        assert!(
+            grid.is_rectangular(),
+            "All rows of a grid must have the same length."
+        );
+        grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds::from_size(self.width as i64, self.height as i64)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.index_of(point).is_some()
+    }
+
+    pub fn get(&self, point: Point) -> Option<u8> {
+        self.index_of(point).map(|index| self.bytes[index])
+    }
+
+    pub fn row(&self, y: usize) -> &'a [u8] {
+        &self.bytes[y * self.stride..y * self.stride + self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// All cells with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, u8)> + '_ {
+        self.rows().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, byte)| (Point::new_usize(x, y), *byte))
+        })
+    }
+
+    /// Position of the first cell with the byte, row by row.
+    pub fn find(&self, byte: u8) -> Option<Point> {
+        self.positions_of(byte).next()
+    }
+
+    /// Positions of all cells with the byte, row by row.
+    pub fn positions_of(&self, byte: u8) -> impl Iterator<Item = Point> + '_ {
+        self.indices_of(byte).map(|index| self.point_of(index))
+    }
+
+    /// The up to 4 neighbours in North, East, South and West direction, that are within the grid.
+    pub fn neighbours_4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point
+            .neighbours_4()
+            .into_iter()
+            .filter(|neighbour| self.contains(*neighbour))
+    }
+
+    /// Index of the cell in the input bytes, for the index based methods below.
+    /// Hot loops can stay on indices and skip the conversion from and to points.
+    pub fn index_of(&self, point: Point) -> Option<usize> {
+        // negative coordinates wrap around to huge values, so one comparison per axis is enough
+        let (x, y) = (point.x as usize, point.y as usize);
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(y * self.stride + x)
+    }
+
+    pub fn point_of(&self, index: usize) -> Point {
+        Point::new_usize(index % self.stride, index / self.stride)
+    }
+
+    /// The cell at an index from [`Self::index_of`], [`Self::indices_of`] or [`Self::step`].
+    pub fn byte(&self, index: usize) -> u8 {
+        self.bytes[index]
+    }
+
+    /// Indices of all cells with the byte, row by row. Scans the input bytes directly.
+    pub fn indices_of(&self, byte: u8) -> impl Iterator<Item = usize> + '_ {
+        // line endings are no cells
+        let bytes = if byte == b'\n' || byte == b'\r' {
+            &[][..]
+        } else {
+            self.bytes
+        };
+        bytes
+            .iter()
+            .enumerate()
+            .filter(move |(_, value)| **value == byte)
+            .map(|(index, _)| index)
+    }
+
+    /// Index of the next cell in the direction, x and y of the direction must be -1, 0 or 1.
+    /// There is no bounds check per axis: a step over the left or right border lands on the line ending
+    /// of a row, and past the last row there are no bytes left.
+    pub fn step(&self, index: usize, direction: Point) -> Option<usize> {
+        debug_assert!(direction.x.abs() <= 1 && direction.y.abs() <= 1);
+        let next = index.wrapping_add_signed(
+            direction.y as isize * self.stride as isize + direction.x as isize,
+        );
+        match self.bytes.get(next) {
+            Some(b'\n' | b'\r') | None => None,
+            Some(_) => Some(next),
+        }
+    }
+
+    /// Same as [`Self::neighbours_4`], but on indices.
+    pub fn neighbours_4_of_index(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(index, direction.step()))
+    }
+
+    /// Same as [`Grid::connected_components`].
+    pub fn connected_components(&self, mut same: impl FnMut(u8, u8) -> bool) -> Vec<Vec<Point>> {
+        let width = self.width;
+        super::connected_components(self.width, self.height, |a, b| {
+            let at = |index: usize| self.bytes[index / width * self.stride + index % width];
+            same(at(a), at(b))
+        })
+    }
+
+    /// Owned copy, e.g. to change cells.
+    pub fn to_grid<T>(&self, f: impl FnMut(u8) -> T) -> Grid<T> {
+        Grid::from_cells(
+            self.width,
+            self.height,
+            self.rows().flatten().copied().map(f).collect(),
+        )
+    }
+
+    /// Every row ends with the same line ending as the first one, only the last one may have none at all.
+    fn is_rectangular(&self) -> bool {
+        let line_ending =
+            &self.bytes[self.width.min(self.bytes.len())..self.stride.min(self.bytes.len())];
+        let length = self.height * self.stride;
+        if self.bytes.len() != length && self.bytes.len() + self.stride - self.width != length {
+            return false;
+        }
+        (0..self.height).all(|y| {
+            let end = (y * self.stride + self.stride).min(self.bytes.len());
+            let ending = &self.bytes[y * self.stride + self.width..end];
+            let is_last = y + 1 == self.height;
+            !self
+                .row(y)
+                .iter()
+                .any(|byte| *byte == b'\n' || *byte == b'\r')
+                && (ending == line_ending || is_last && ending.is_empty())
+        })
+    }
+}
+
+impl Index<Point> for ByteGrid<'_> {
+    type Output = u8;
+
+    fn index(&self, point: Point) -> &u8 {
+        let index = self.index_of(point).expect("Point is out of bounds.");
+        &self.bytes[index]
+    }
+}
@@ -0,0 +1,182 @@
+use crate::geometry::Point;
+
+use super::Grid;
+
+#[test]
+pub fn test_history() {
+    let mut history = History::new(Grid::parse("...\n...", |ch| ch));
+
+    history.set(Point::new(0, 0), '^');
+    history.commit();
+    history.set(Point::new(0, 0), 'X');
+    history.set(Point::new(1, 0), '^');
+    // setting the same value again is no change
+    history.set(Point::new(2, 1), '.');
+    history.commit();
+    history.set(Point::new(1, 0), 'X');
+    history.commit();
+
+    assert_eq!(history.step_count(), 3);
+    assert_eq!(history.changes(1).len(), 2);
+    assert_eq!(history.grid().to_string(), "XX.\n...\n");
+    assert_eq!(history.frame(0).to_string(), "...\n...\n");
+    assert_eq!(history.frame(2).to_string(), "X^.\n...\n");
+
+    let mut replay = history.replay();
+    assert_eq!(replay.grid().to_string(), "...\n...\n");
+    assert!(replay.backward().is_none());
+    replay.forward();
+    replay.forward();
+    assert_eq!(replay.grid(), &history.frame(2));
+    replay.backward();
+    assert_eq!(replay.grid(), &history.frame(1));
+    replay.seek(3);
+    assert_eq!(replay.grid(), history.grid());
+    assert!(replay.forward().is_none());
+}
+
+/// A single changed cell of one simulation step.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CellChange<T> {
+    pub point: Point,
+    pub before: T,
+    pub after: T,
+}
+
+/// Records every change to a grid, grouped into steps, so any earlier frame of a simulation can be reconstructed.
+/// Only the current grid and the diffs are stored, not a copy per step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct History<T> {
+    grid: Grid<T>,
+    steps: Vec<Vec<CellChange<T>>>,
+    pending: Vec<CellChange<T>>,
+}
+
+impl<T: Clone + PartialEq> History<T> {
+    /// Start recording with the grid as frame 0.
+    pub fn new(grid: Grid<T>) -> Self {
+        Self {
+            grid,
+            steps: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// The current state, including changes that are not committed yet.
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+
+    /// Change a cell and record it for the current step. Returns the old value.
+    /// Out of bounds points are ignored and return None, like Grid::set.
+    pub fn set(&mut self, point: Point, value: T) -> Option<T> {
+        let before = self.grid.get(point)?.clone();
+        if before != value {
+            self.pending.push(CellChange {
+                point,
+                before: before.clone(),
+                after: value.clone(),
+            });
+            self.grid.set(point, value);
+        }
+        Some(before)
+    }
+
+    /// Finish the current step. Steps without changes are recorded as well, so step numbers stay in sync with the simulation.
+    pub fn commit(&mut self) {
+        self.steps.push(std::mem::take(&mut self.pending));
+    }
+
+    /// Number of committed steps. Frames go from 0 to step_count.
+    pub fn step_count(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Changes of a committed step. Step 0 turns frame 0 into frame 1.
+    pub fn changes(&self, step: usize) -> &[CellChange<T>] {
+        &self.steps[step]
+    }
+
+    /// Reconstruct the grid after the given number of committed steps.
+    pub fn frame(&self, frame: usize) -> Grid<T> {
+        assert!(
+            frame <= self.step_count(),
+            "Frame {} does not exist, only {} steps are recorded.",
+            frame,
+            self.step_count()
+        );
+        // going backwards from the current state is as good as any other start
+        let mut grid = self.grid.clone();
+        undo(&mut grid, &self.pending);
+        self.steps[frame..]
+            .iter()
+            .rev()
+            .for_each(|changes| undo(&mut grid, changes));
+        grid
+    }
+
+    /// Walk through the recorded frames, starting at frame 0.
+    pub fn replay(&self) -> Replay<'_, T> {
+        Replay {
+            history: self,
+            grid: self.frame(0),
+            frame: 0,
+        }
+    }
+}
+
+/// Cursor over the frames of a [`History`], that can move in both directions.
+#[derive(Debug, Clone)]
+pub struct Replay<'a, T> {
+    history: &'a History<T>,
+    grid: Grid<T>,
+    frame: usize,
+}
+
+impl<'a, T: Clone + PartialEq> Replay<'a, T> {
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Apply the next step. Returns its changes, or None if the last frame is already reached.
+    pub fn forward(&mut self) -> Option<&'a [CellChange<T>]> {
+        let changes = self.history.steps.get(self.frame)?;
+        changes.iter().for_each(|change| {
+            self.grid.set(change.point, change.after.clone());
+        });
+        self.frame += 1;
+        Some(changes)
+    }
+
+    /// Revert the previous step. Returns its changes, or None if frame 0 is already reached.
+    pub fn backward(&mut self) -> Option<&'a [CellChange<T>]> {
+        if self.frame == 0 {
+            return None;
+        }
+        self.frame -= 1;
+        let changes = &self.history.steps[self.frame];
+        undo(&mut self.grid, changes);
+        Some(changes)
+    }
+
+    /// Move forwards or backwards to the given frame.
+    pub fn seek(&mut self, frame: usize) {
+        while self.frame < frame && self.forward().is_some() {}
+        while self.frame > frame && self.backward().is_some() {}
+    }
+}
+
+fn undo<T: Clone>(grid: &mut Grid<T>, changes: &[CellChange<T>]) {
+    // reverse order, in case a cell was changed multiple times in one step
+    changes.iter().rev().for_each(|change| {
+        grid.set(change.point, change.before.clone());
+    });
+}
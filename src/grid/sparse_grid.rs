@@ -0,0 +1,236 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::geometry::{Bounds, Point};
+
+use super::Grid;
+
+#[test]
+pub fn test_sparse_grid() {
+    let mut grid: SparseGrid<char> = SparseGrid::new();
+    assert_eq!(grid.bounds(), None);
+    assert_eq!(grid.rows().count(), 0);
+
+    grid.set(Point::new(-2, 1), 'a');
+    grid.set(Point::new(1, -1), 'b');
+    grid.set(Point::new(0, 0), 'c');
+    assert_eq!(
+        grid.bounds(),
+        Some(Bounds::new(Point::new(-2, -1), Point::new(2, 2)))
+    );
+    assert_eq!(grid.render('.', |ch| *ch), "...b\n..c.\na...\n");
+    assert_eq!(grid[Point::new(1, -1)], 'b');
+    grid[Point::new(0, 0)] = 'd';
+    assert_eq!(grid.rows().nth(1), Some(vec![None, None, Some(&'d'), None]));
+    assert_eq!(grid.rows().count(), 3);
+
+    // removing a point on the edge shrinks the bounds again
+    assert_eq!(grid.remove(Point::new(-2, 1)), Some('a'));
+    assert_eq!(
+        grid.bounds(),
+        Some(Bounds::new(Point::new(0, -1), Point::new(2, 1)))
+    );
+
+    let dense = grid.to_grid('.').unwrap();
+    assert_eq!(dense.to_string(), ".b\nd.\n");
+
+    let sparse = SparseGrid::from_grid(&dense, |ch| *ch != '.');
+    assert_eq!(sparse.len(), 2);
+    assert_eq!(sparse.get(Point::new(0, 1)), Some(&'d'));
+}
+
+/// Grid that only stores the cells that have been set, at any signed coordinate.
+/// Keeps track of the bounding box of all set cells, to render them in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    bounds: Option<Bounds>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy all cells of a dense grid, that match the filter. Positions stay the same.
+    pub fn from_grid(grid: &Grid<T>, mut filter: impl FnMut(&T) -> bool) -> Self
+    where
+        T: Clone,
+    {
+        let mut sparse = Self::new();
+        grid.iter()
+            .filter(|(_, value)| filter(value))
+            .for_each(|(point, value)| {
+                sparse.set(point, value.clone());
+            });
+        sparse
+    }
+
+    /// Convert to a dense grid covering the bounding box. Cells that are not set get the empty value.
+    /// The dense grid always starts at (0, 0), so all positions are moved by the top left corner of the bounding box.
+    /// Returns None, if nothing is set.
+    pub fn to_grid(&self, empty: T) -> Option<Grid<T>>
+    where
+        T: Clone,
+    {
+        let bounds = self.bounds?;
+        let mut grid = Grid::new(bounds.width() as usize, bounds.height() as usize, empty);
+        self.cells.iter().for_each(|(point, value)| {
+            grid.set(*point - bounds.min, value.clone());
+        });
+        Some(grid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Smallest rectangle that contains all set cells. None, if nothing is set.
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    /// Is there a value at the point
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    /// Get the value at the point, or insert a new one first.
+    pub fn get_or_insert_with(&mut self, point: Point, f: impl FnOnce() -> T) -> &mut T {
+        self.extend_bounds(point);
+        self.cells.entry(point).or_insert_with(f)
+    }
+
+    /// Set the value at the point and return the old one.
+    pub fn set(&mut self, point: Point, value: T) -> Option<T> {
+        self.extend_bounds(point);
+        self.cells.insert(point, value)
+    }
+
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        let value = self.cells.remove(&point)?;
+
+        let on_edge = self.bounds.is_some_and(|bounds| {
+            point.x == bounds.min.x
+                || point.y == bounds.min.y
+                || point.x == bounds.max.x - 1
+                || point.y == bounds.max.y - 1
+        });
+        if on_edge {
+            // the bounding box might shrink, simply calculate it again
+            self.bounds = None;
+            let points: Vec<Point> = self.cells.keys().copied().collect();
+            points
+                .into_iter()
+                .for_each(|point| self.extend_bounds(point));
+        }
+        Some(value)
+    }
+
+    /// All set cells with their position, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(point, value)| (*point, value))
+    }
+
+    /// All set positions, in no particular order.
+    pub fn positions(&self) -> impl Iterator<Item = Point> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// Position of any set cell that matches.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter()
+            .find(|(_, value)| predicate(value))
+            .map(|(point, _)| point)
+    }
+
+    /// The up to 4 neighbours in North, East, South and West direction, that are set.
+    pub fn neighbours_4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point
+            .neighbours_4()
+            .into_iter()
+            .filter(|neighbour| self.contains(*neighbour))
+    }
+
+    /// Rows of the bounding box, top to bottom. Cells that are not set are None.
+    pub fn rows(&self) -> impl Iterator<Item = Vec<Option<&T>>> {
+        self.bounds.into_iter().flat_map(move |bounds| {
+            (bounds.min.y..bounds.max.y).map(move |y| {
+                (bounds.min.x..bounds.max.x)
+                    .map(|x| self.cells.get(&Point::new(x, y)))
+                    .collect()
+            })
+        })
+    }
+
+    /// Render the bounding box row by row, top to bottom and left to right.
+    /// Cells that are not set are rendered as the empty value.
+    pub fn render<D: Display>(&self, empty: impl Display, f: impl Fn(&T) -> D) -> String {
+        let mut s = String::new();
+        let empty = empty.to_string();
+        for row in self.rows() {
+            for cell in row {
+                match cell {
+                    Some(value) => s.push_str(&f(value).to_string()),
+                    None => s.push_str(&empty),
+                }
+            }
+            s.push('\n');
+        }
+        s
+    }
+
+    fn extend_bounds(&mut self, point: Point) {
+        let bounds = self
+            .bounds
+            .get_or_insert(Bounds::new(point, point + Point::new(1, 1)));
+        bounds.min.x = bounds.min.x.min(point.x);
+        bounds.min.y = bounds.min.y.min(point.y);
+        bounds.max.x = bounds.max.x.max(point.x + 1);
+        bounds.max.y = bounds.max.y.max(point.y + 1);
+    }
+}
+
+impl<T> Index<Point> for SparseGrid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point).expect("Point is not set.")
+    }
+}
+
+impl<T> IndexMut<Point> for SparseGrid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point).expect("Point is not set.")
+    }
+}
+
+impl<T: Clone> From<&Grid<T>> for SparseGrid<T> {
+    fn from(grid: &Grid<T>) -> Self {
+        Self::from_grid(grid, |_| true)
+    }
+}
@@ -0,0 +1,188 @@
+use std::fmt::Display;
+
+use crate::geometry::{Bounds, Point};
+
+use super::Grid;
+
+#[test]
+pub fn test_wrapping_grid() {
+    let mut grid = WrappingGrid::new(11, 7, 0);
+
+    assert_eq!(grid.wrap(Point::new(-1, 7)), Point::new(10, 0));
+    grid.set(Point::new(-1, -1), 5);
+    assert_eq!(grid.get(Point::new(10, 6)), &5);
+
+    // robot from the day 14 example, after 5 seconds
+    let position = grid.advance(Point::new(2, 4), Point::new(2, -3), 5);
+    assert_eq!(position, Point::new(1, 3));
+    // the same in single steps
+    let mut stepped = Point::new(2, 4);
+    (0..5).for_each(|_| stepped = grid.advance(stepped, Point::new(2, -3), 1));
+    assert_eq!(stepped, position);
+    // huge step counts must not overflow
+    let position = grid.advance(Point::new(2, 4), Point::new(2, -3), i64::MAX);
+    assert!(grid.bounds().contains(&position));
+}
+
+#[test]
+pub fn test_region_counts() {
+    let grid = WrappingGrid::new(11, 7, ());
+
+    // the middle row and column are not part of any quadrant
+    let points = [
+        Point::new(0, 0),
+        Point::new(4, 2),
+        Point::new(5, 0),
+        Point::new(6, 3),
+        Point::new(10, 6),
+        Point::new(6, 4),
+    ];
+    assert_eq!(grid.quadrant_counts(points), [2, 0, 0, 2]);
+
+    // without a remainder, there are no centre lines
+    let grid = WrappingGrid::new(4, 4, ());
+    assert_eq!(
+        grid.region_counts((0..4).map(|x| Point::new(x, 0)), 2, 1),
+        vec![2, 2]
+    );
+}
+
+/// Grid on a torus: every coordinate wraps around at the edges, so every point is valid.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WrappingGrid<T> {
+    grid: Grid<T>,
+}
+
+impl<T: Clone> WrappingGrid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self::from_grid(Grid::new(width, height, value))
+    }
+}
+
+impl<T> WrappingGrid<T> {
+    /// The grid must not be empty, otherwise there is nothing to wrap around.
+    pub fn from_grid(grid: Grid<T>) -> Self {
+        assert!(
+            grid.width() > 0 && grid.height() > 0,
+            "A wrapping grid cannot be empty."
+        );
+        Self { grid }
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.height()
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        self.grid.bounds()
+    }
+
+    /// Move any point into the grid. Negative coordinates wrap around from the other side.
+    pub fn wrap(&self, point: Point) -> Point {
+        Point::new(
+            point.x.rem_euclid(self.width() as i64),
+            point.y.rem_euclid(self.height() as i64),
+        )
+    }
+
+    /// Position of a linear mover after the given number of steps.
+    /// Calculated directly instead of step by step, with intermediate values in i128, so any step count works.
+    pub fn advance(&self, position: Point, velocity: Point, steps: i64) -> Point {
+        let advance_axis = |position: i64, velocity: i64, length: usize| -> i64 {
+            (position as i128 + velocity as i128 * steps as i128).rem_euclid(length as i128) as i64
+        };
+        Point::new(
+            advance_axis(position.x, velocity.x, self.width()),
+            advance_axis(position.y, velocity.y, self.height()),
+        )
+    }
+
+    pub fn get(&self, point: Point) -> &T {
+        &self.grid[self.wrap(point)]
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> &mut T {
+        let point = self.wrap(point);
+        &mut self.grid[point]
+    }
+
+    /// Replace the value at the wrapped point and return the old one.
+    pub fn set(&mut self, point: Point, value: T) -> T {
+        std::mem::replace(self.get_mut(point), value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.grid.iter()
+    }
+
+    pub fn render<D: Display>(&self, f: impl Fn(&T) -> D) -> String {
+        self.grid.render(f)
+    }
+
+    /// Count the points per quadrant, in the order top left, top right, bottom left, bottom right.
+    /// See region_counts for how the centre lines are handled.
+    pub fn quadrant_counts(&self, points: impl IntoIterator<Item = Point>) -> [usize; 4] {
+        let counts = self.region_counts(points, 2, 2);
+        [counts[0], counts[1], counts[2], counts[3]]
+    }
+
+    /// Split the grid into columns x rows equally sized regions and count the (wrapped) points in each region.
+    /// Counts are returned row by row.
+    ///
+    /// If the length of an axis divides evenly into the parts, the regions simply touch.
+    /// If exactly one line is left over between each pair of regions (e.g. 101 = 50 + 1 + 50),
+    /// those centre lines belong to no region and points on them are not counted.
+    /// Any other split would create regions of different sizes and panics.
+    pub fn region_counts(
+        &self,
+        points: impl IntoIterator<Item = Point>,
+        columns: usize,
+        rows: usize,
+    ) -> Vec<usize> {
+        let mut counts = vec![0; columns * rows];
+        points.into_iter().for_each(|point| {
+            let point = self.wrap(point);
+            let column = region_index(point.x as usize, self.width(), columns);
+            let row = region_index(point.y as usize, self.height(), rows);
+            if let (Some(column), Some(row)) = (column, row) {
+                counts[row * columns + column] += 1;
+            }
+        });
+        counts
+    }
+}
+
+/// Which of the equally sized parts of an axis the coordinate is in. None if it is on a centre line.
+fn region_index(coordinate: usize, length: usize, parts: usize) -> Option<usize> {
+    assert!(
+        parts > 0 && parts <= length,
+        "Cannot split {} fields into {} parts.",
+        length,
+        parts
+    );
+    let size = length / parts;
+    let remainder = length % parts;
+
+    if remainder == 0 {
+        return Some(coordinate / size);
+    }
+    assert!(
+        remainder == parts - 1,
+        "Cannot split {} fields into {} equal parts with centre lines.",
+        length,
+        parts
+    );
+    // every part is followed by one centre line, except the last one
+    if coordinate % (size + 1) == size {
+        return None;
+    }
+    Some(coordinate / (size + 1))
+}
@@ -1,10 +1,78 @@
 use std::fs;
 
+#[test]
+pub fn test_normalise() {
+    assert_eq!(normalise("\u{feff}a\r\nb\r\n"), "a\nb");
+    assert_eq!(normalise("a\n\n"), "a\n");
+    assert_eq!(normalise(""), "");
+
+    assert_eq!(lines("a\r\nb\n").collect::<Vec<_>>(), vec!["a", "b"]);
+    assert_eq!(lines("a\n\nb").collect::<Vec<_>>(), vec!["a", "", "b"]);
+    assert_eq!(lines("\n").count(), 0);
+    assert_eq!(lines("").count(), 0);
+
+    assert_eq!(
+        sections("a\r\nb\r\n\r\nc\n\n\nd\n").collect::<Vec<_>>(),
+        vec!["a\r\nb", "c", "d"]
+    );
+}
+
 /// Load input from private repo cloned next to this repo.
 /// Format: ../aoc_input/2024/day_01.txt
 pub fn load_file(year: &str, day: &str) -> String {
     let mut relative_path = "../aoc_input/".to_owned();
     relative_path = relative_path + year + "/day_" + day + ".txt";
 
-    fs::read_to_string(relative_path).expect("Cannot read file.")
+    normalise(&fs::read_to_string(relative_path).expect("Cannot read file."))
+}
+
+/// Strip a byte order mark, turn CRLF into LF and drop one trailing newline,
+/// so every day sees the same input, no matter how the file was saved.
+pub fn normalise(input: &str) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut normalised = input.replace("\r\n", "\n");
+    if normalised.ends_with('\n') {
+        normalised.pop();
+    }
+    normalised
+}
+
+/// Lines without their line endings. Works for LF and CRLF, one trailing newline does not add an empty line.
+/// Use this instead of split("\n"), so sample inputs and files are handled the same way.
+pub fn lines(input: &str) -> impl Iterator<Item = &str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let input = input
+        .strip_suffix('\n')
+        .map(|input| input.strip_suffix('\r').unwrap_or(input))
+        .unwrap_or(input);
+    // an empty input has no lines at all, instead of a single empty one
+    input
+        .split('\n')
+        .take(if input.is_empty() { 0 } else { usize::MAX })
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+}
+
+/// Blocks of lines, that are separated by one or more empty lines. Line endings inside a block are kept.
+pub fn sections(input: &str) -> impl Iterator<Item = &str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut sections = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+    let mut position = 0;
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.is_empty() {
+            if let Some(start) = start.take() {
+                sections.push(&input[start..end]);
+            }
+        } else {
+            start.get_or_insert(position);
+            end = position + content.len();
+        }
+        position += line.len();
+    }
+    if let Some(start) = start {
+        sections.push(&input[start..end]);
+    }
+    sections.into_iter()
 }
@@ -0,0 +1,245 @@
+use std::ops::Range;
+
+#[test]
+pub fn test_lexer() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Kind {
+        Mul,
+        Do,
+        Dont,
+    }
+    let lexer = Lexer::new()
+        .pattern(Kind::Mul, "mul({int:3},{int:3})")
+        .pattern(Kind::Do, "do()")
+        .pattern(Kind::Dont, "don't()");
+
+    let instructions: Vec<Instruction<Kind>> =
+        lexer.scan("mmul(2,4)don't()mul(1234,1)do()").collect();
+    assert_eq!(
+        instructions,
+        vec![
+            Instruction {
+                kind: Kind::Mul,
+                args: vec![Arg::Int(2), Arg::Int(4)],
+                span: 1..9
+            },
+            Instruction {
+                kind: Kind::Dont,
+                args: vec![],
+                span: 9..16
+            },
+            Instruction {
+                kind: Kind::Do,
+                args: vec![],
+                span: 27..31
+            },
+        ]
+    );
+    assert_eq!(instructions[0].int(1), 4);
+
+    // the longest pattern wins, even if a shorter one matches as well
+    let lexer = Lexer::new()
+        .pattern("short", "do")
+        .pattern("long", "don't")
+        .pattern("move", "{word} {signed}");
+    let kinds: Vec<&str> = lexer
+        .scan("don't do up -3")
+        .map(|instruction| instruction.kind)
+        .collect();
+    assert_eq!(kinds, vec!["long", "short", "move"]);
+    let last = lexer.scan("up -3").next().unwrap();
+    assert_eq!(last.args, vec![Arg::Word("up"), Arg::Int(-3)]);
+}
+
+/// Typed argument of an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arg<'a> {
+    Int(i64),
+    Word(&'a str),
+}
+
+/// A pattern, that was found in the input. The span is the byte range in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction<'a, K> {
+    pub kind: K,
+    pub args: Vec<Arg<'a>>,
+    pub span: Range<usize>,
+}
+
+impl<'a, K> Instruction<'a, K> {
+    /// Panics, if the argument is not an integer.
+    pub fn int(&self, index: usize) -> i64 {
+        match self.args[index] {
+            Arg::Int(value) => value,
+            arg => panic!("Argument {} is no integer, but {:?}.", index, arg),
+        }
+    }
+
+    /// Panics, if the argument is not a word.
+    pub fn word(&self, index: usize) -> &'a str {
+        match self.args[index] {
+            Arg::Word(value) => value,
+            arg => panic!("Argument {} is no word, but {:?}.", index, arg),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Literal(String),
+    Int { signed: bool, max_digits: usize },
+    Word,
+}
+
+/// Finds instructions in a corrupted stream, everything that does not match any pattern is skipped.
+/// Patterns are literal text with typed placeholders:
+/// {int} unsigned integer, {int:3} with at most 3 digits, {signed} integer with optional minus,
+/// {word} ascii letters, digits and underscores.
+/// At every offset the longest matching pattern wins, if nothing matches the next offset is tried,
+/// so overlapping prefixes like "mmul(" are found as well.
+#[derive(Debug, Clone)]
+pub struct Lexer<K> {
+    patterns: Vec<(K, Vec<Token>)>,
+}
+
+impl<K: Copy> Default for Lexer<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Copy> Lexer<K> {
+    pub fn new() -> Self {
+        Self {
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Add a pattern. Patterns added first win, if two match with the same length.
+    /// Panics on unknown placeholders.
+    pub fn pattern(mut self, kind: K, pattern: &str) -> Self {
+        let mut tokens = Vec::new();
+        let mut rest = pattern;
+        while !rest.is_empty() {
+            if let Some(placeholder) = rest.strip_prefix('{') {
+                let end = placeholder
+                    .find('}')
+                    .unwrap_or_else(|| panic!("Unclosed placeholder in pattern {:?}.", pattern));
+                let (name, max_digits) = match placeholder[..end].split_once(':') {
+                    Some((name, max_digits)) => {
+                        (name, max_digits.parse().expect("Invalid digit count."))
+                    }
+                    None => (&placeholder[..end], usize::MAX),
+                };
+                tokens.push(match name {
+                    "int" => Token::Int {
+                        signed: false,
+                        max_digits,
+                    },
+                    "signed" => Token::Int {
+                        signed: true,
+                        max_digits,
+                    },
+                    "word" => Token::Word,
+                    _ => panic!("Unknown placeholder {{{}}}.", &placeholder[..end]),
+                });
+                rest = &placeholder[end + 1..];
+                continue;
+            }
+            let end = rest.find('{').unwrap_or(rest.len());
+            tokens.push(Token::Literal(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+        assert!(!tokens.is_empty(), "Empty pattern.");
+        self.patterns.push((kind, tokens));
+        self
+    }
+
+    /// All instructions in the input, they never overlap.
+    pub fn scan<'a>(&'a self, input: &'a str) -> impl Iterator<Item = Instruction<'a, K>> + 'a {
+        let mut offset = 0;
+        std::iter::from_fn(move || {
+            while offset < input.len() {
+                if !input.is_char_boundary(offset) {
+                    offset += 1;
+                    continue;
+                }
+                let longest = self
+                    .patterns
+                    .iter()
+                    .filter_map(|(kind, tokens)| {
+                        match_tokens(tokens, input, offset).map(|(end, args)| Instruction {
+                            kind: *kind,
+                            args,
+                            span: offset..end,
+                        })
+                    })
+                    // max_by_key returns the last maximum, but earlier patterns should win
+                    .reduce(|best, instruction| {
+                        if instruction.span.end > best.span.end {
+                            instruction
+                        } else {
+                            best
+                        }
+                    });
+                match longest {
+                    Some(instruction) => {
+                        offset = instruction.span.end;
+                        return Some(instruction);
+                    }
+                    None => offset += 1,
+                }
+            }
+            None
+        })
+    }
+}
+
+/// End of the match and the arguments, None if the tokens do not match at the position.
+fn match_tokens<'a>(
+    tokens: &[Token],
+    input: &'a str,
+    start: usize,
+) -> Option<(usize, Vec<Arg<'a>>)> {
+    let bytes = input.as_bytes();
+    let mut position = start;
+    let mut args = Vec::new();
+    for token in tokens {
+        match token {
+            Token::Literal(literal) => {
+                if !input[position..].starts_with(literal.as_str()) {
+                    return None;
+                }
+                position += literal.len();
+            }
+            Token::Int { signed, max_digits } => {
+                let number_start = position;
+                if *signed && bytes.get(position) == Some(&b'-') {
+                    position += 1;
+                }
+                let digits_start = position;
+                while position < bytes.len() && bytes[position].is_ascii_digit() {
+                    position += 1;
+                }
+                let digits = position - digits_start;
+                if digits == 0 || digits > *max_digits {
+                    return None;
+                }
+                args.push(Arg::Int(input[number_start..position].parse().ok()?));
+            }
+            Token::Word => {
+                let word_start = position;
+                while position < bytes.len()
+                    && (bytes[position].is_ascii_alphanumeric() || bytes[position] == b'_')
+                {
+                    position += 1;
+                }
+                if position == word_start {
+                    return None;
+                }
+                args.push(Arg::Word(&input[word_start..position]));
+            }
+        }
+    }
+    Some((position, args))
+}
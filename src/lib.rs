@@ -2,8 +2,32 @@
 #![allow(unused_variables)]
 #![allow(unused_mut)]
 
+// the derive macros refer to this crate by name, also from inside of it
+extern crate self as advent_of_code;
+
 pub mod aoc_2024;
+pub mod arith;
+pub mod combinatorics;
+pub mod cycle;
+pub mod dag;
+pub mod digits;
+pub mod disjoint_set;
+pub mod dot;
+pub mod equation;
+pub mod extract;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
 pub mod input;
+pub mod lexer;
+pub mod linear;
+pub mod math;
+pub mod ordering;
+pub mod puzzle_parse;
+pub mod range_set;
+pub mod runner;
+pub mod scan;
+pub mod search;
 
 pub fn run_all() {
     aoc_2024::run_all();
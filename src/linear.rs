@@ -0,0 +1,497 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use crate::{
+    arith::{add, mul},
+    math::{checked_lcm_i128, crt_i128, gcd_i128, mod_inverse_i128},
+};
+
+#[test]
+pub fn test_rational() {
+    let half = Rational::new(2, 4);
+    assert_eq!(half, Rational::new(-1, -2));
+    assert_eq!(half.to_string(), "1/2");
+    assert_eq!(half + half, Rational::ONE);
+    assert_eq!((half - Rational::integer(2)).to_string(), "-3/2");
+    assert_eq!(Rational::new(-3, 2).floor(), -2);
+    assert_eq!(Rational::new(-3, 2).ceil(), -1);
+    assert!(Rational::new(1, 3) < half);
+    assert_eq!((half * Rational::integer(6)).to_integer(), Some(3));
+    assert_eq!((half / Rational::integer(3)).to_integer(), None);
+}
+
+#[test]
+pub fn test_solve() {
+    let unique = solve(&[vec![94, 22], vec![34, 67]], &[8400, 5400]);
+    assert_eq!(
+        unique,
+        Solution::Unique(vec![Rational::integer(80), Rational::integer(40)])
+    );
+
+    let unique = solve(
+        &[vec![2, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]],
+        &[8, -11, -3],
+    );
+    assert_eq!(
+        unique,
+        Solution::Unique(vec![
+            Rational::integer(2),
+            Rational::integer(3),
+            Rational::integer(-1)
+        ])
+    );
+
+    assert_eq!(solve(&[vec![1, 2], vec![2, 4]], &[3, 7]), Solution::None);
+
+    let Solution::Infinite {
+        particular,
+        null_space,
+    } = solve(&[vec![1, 2], vec![2, 4]], &[3, 6])
+    else {
+        panic!("Expected infinitely many solutions.");
+    };
+    assert_eq!(particular, vec![Rational::integer(3), Rational::ZERO]);
+    assert_eq!(null_space, vec![vec![Rational::integer(-2), Rational::ONE]]);
+}
+
+#[test]
+pub fn test_min_cost_solution() {
+    // buttons (94, 34) and (22, 67), the A button costs 3
+    assert_eq!(
+        min_cost_solution(&[vec![94, 22], vec![34, 67]], &[8400, 5400], &[3, 1]),
+        Ok(vec![80, 40])
+    );
+    // no whole number of presses
+    assert_eq!(
+        min_cost_solution(&[vec![26, 67], vec![66, 21]], &[12748, 12176], &[3, 1]),
+        Err(CostError::NoSolution)
+    );
+    // collinear buttons (4, 4) and (1, 1): a = 2, b = 2 is cheaper than pressing only b
+    assert_eq!(
+        min_cost_solution(&[vec![4, 1], vec![4, 1]], &[10, 10], &[3, 1]),
+        Ok(vec![2, 2])
+    );
+    // collinear, but only even positions can be reached
+    assert_eq!(
+        min_cost_solution(&[vec![2, 4], vec![2, 4]], &[5, 5], &[3, 1]),
+        Err(CostError::NoSolution)
+    );
+    // buttons, that do not move at all, leave both variables free
+    assert_eq!(
+        min_cost_solution(&[vec![0, 0], vec![0, 0]], &[0, 0], &[3, 1]),
+        Err(CostError::TooManyFreeVariables(2))
+    );
+    // a direction, whose common denominator does not fit
+    let direction = [i64::MAX, i64::MAX - 1, i64::MAX - 2].map(|d| Rational::new(1, d as i128));
+    assert_eq!(primitive_direction(&direction), Err(CostError::Overflow));
+    assert_eq!(
+        primitive_direction(&[Rational::new(1, 2), Rational::new(-1, 3)]),
+        Ok(vec![3, -2])
+    );
+}
+
+/// Exact fraction, always reduced and with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational {
+        numerator: 0,
+        denominator: 1,
+    };
+    pub const ONE: Rational = Rational {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "Denominator must not be 0.");
+        // the gcd is never 0, because the denominator is not
+        let divisor = gcd_i128(numerator, denominator) * denominator.signum();
+        Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        }
+    }
+
+    pub fn integer(value: i128) -> Self {
+        Self {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    pub fn floor(&self) -> i128 {
+        self.numerator.div_euclid(self.denominator)
+    }
+
+    pub fn ceil(&self) -> i128 {
+        -(-self.numerator).div_euclid(self.denominator)
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Rational {
+        Rational::new(
+            add(
+                mul(self.numerator, other.denominator),
+                mul(other.numerator, self.denominator),
+            ),
+            mul(self.denominator, other.denominator),
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational {
+        self + -other
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Rational {
+        Rational::new(
+            mul(self.numerator, other.numerator),
+            mul(self.denominator, other.denominator),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, other: Rational) -> Rational {
+        Rational::new(
+            mul(self.numerator, other.denominator),
+            mul(self.denominator, other.numerator),
+        )
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // denominators are positive, so cross multiplying keeps the order
+        mul(self.numerator, other.denominator).cmp(&mul(other.numerator, self.denominator))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+/// Outcome of solving a linear system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    Unique(Vec<Rational>),
+    None,
+    /// Every particular + sum of k_i * null_space[i] is a solution.
+    /// The particular solution has all free variables set to 0.
+    Infinite {
+        particular: Vec<Rational>,
+        null_space: Vec<Vec<Rational>>,
+    },
+}
+
+/// Solve matrix * x = rhs exactly with gaussian elimination. The matrix is given row by row,
+/// e.g. a * ax + b * bx = px and a * ay + b * by = py is [[ax, bx], [ay, by]] and [px, py].
+pub fn solve(matrix: &[Vec<i64>], rhs: &[i64]) -> Solution {
+    let columns = matrix.first().map(|row| row.len()).unwrap_or(0);
+    assert_eq!(
+        matrix.len(),
+        rhs.len(),
+        "Every row needs a right hand side."
+    );
+    assert!(
+        matrix.iter().all(|row| row.len() == columns),
+        "All rows must have the same length."
+    );
+
+    // augmented matrix, the last column is the right hand side
+    let mut rows: Vec<Vec<Rational>> = matrix
+        .iter()
+        .zip(rhs)
+        .map(|(row, value)| {
+            row.iter()
+                .chain([value])
+                .map(|value| Rational::integer(*value as i128))
+                .collect()
+        })
+        .collect();
+
+    // reduced row echelon form, remembering the pivot column of every row
+    let mut pivots: Vec<usize> = Vec::new();
+    for column in 0..columns {
+        let row = pivots.len();
+        if row == rows.len() {
+            break;
+        }
+        let Some(pivot) = (row..rows.len()).find(|r| !rows[*r][column].is_zero()) else {
+            // free variable
+            continue;
+        };
+        rows.swap(row, pivot);
+        let factor = rows[row][column];
+        rows[row]
+            .iter_mut()
+            .for_each(|value| *value = *value / factor);
+        let pivot_row = rows[row].clone();
+        for (other, values) in rows.iter_mut().enumerate() {
+            let factor = values[column];
+            if other == row || factor.is_zero() {
+                continue;
+            }
+            values
+                .iter_mut()
+                .zip(&pivot_row)
+                .for_each(|(value, pivot_value)| *value = *value - factor * *pivot_value);
+        }
+        pivots.push(column);
+    }
+
+    // a row 0 = value with value != 0 is a contradiction
+    if rows[pivots.len()..]
+        .iter()
+        .any(|row| !row[columns].is_zero())
+    {
+        return Solution::None;
+    }
+
+    let mut particular = vec![Rational::ZERO; columns];
+    pivots
+        .iter()
+        .enumerate()
+        .for_each(|(row, column)| particular[*column] = rows[row][columns]);
+    if pivots.len() == columns {
+        return Solution::Unique(particular);
+    }
+
+    let null_space = (0..columns)
+        .filter(|column| !pivots.contains(column))
+        .map(|free| {
+            let mut direction = vec![Rational::ZERO; columns];
+            direction[free] = Rational::ONE;
+            pivots
+                .iter()
+                .enumerate()
+                .for_each(|(row, column)| direction[*column] = -rows[row][free]);
+            direction
+        })
+        .collect();
+    Solution::Infinite {
+        particular,
+        null_space,
+    }
+}
+
+/// Why [`min_cost_solution`] has no result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CostError {
+    /// There is no non-negative integer solution, or the cost gets lower without end.
+    NoSolution,
+    /// The solutions span more than a line, the number of free variables is given.
+    TooManyFreeVariables(usize),
+    /// The common denominator of the solutions does not fit in an i128.
+    Overflow,
+}
+
+impl Display for CostError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CostError::NoSolution => write!(f, "There is no non-negative integer solution."),
+            CostError::TooManyFreeVariables(count) => write!(
+                f,
+                "Only a single free variable is supported, the system has {}.",
+                count
+            ),
+            CostError::Overflow => write!(f, "The common denominator does not fit in an i128."),
+        }
+    }
+}
+
+/// The non-negative integer solution with the lowest cost, where the cost is the sum of cost[i] * x[i].
+/// If there are infinitely many solutions, only a single free variable is supported.
+pub fn min_cost_solution(
+    matrix: &[Vec<i64>],
+    rhs: &[i64],
+    cost: &[i64],
+) -> Result<Vec<i64>, CostError> {
+    match solve(matrix, rhs) {
+        Solution::None => Err(CostError::NoSolution),
+        Solution::Unique(solution) => solution
+            .iter()
+            .map(|value| {
+                value
+                    .to_integer()
+                    .filter(|value| *value >= 0)
+                    .and_then(|value| i64::try_from(value).ok())
+            })
+            .collect::<Option<Vec<i64>>>()
+            .ok_or(CostError::NoSolution),
+        Solution::Infinite {
+            particular,
+            null_space,
+        } => {
+            if null_space.len() > 1 {
+                return Err(CostError::TooManyFreeVariables(null_space.len()));
+            }
+            min_cost_on_line(&particular, &null_space[0], cost)
+        }
+    }
+}
+
+/// Cheapest non-negative integer point on the line particular + t * direction.
+fn min_cost_on_line(
+    particular: &[Rational],
+    direction: &[Rational],
+    cost: &[i64],
+) -> Result<Vec<i64>, CostError> {
+    let direction = primitive_direction(direction)?;
+    // every integer point has x = (offsets + s * direction) / scale for an integer s,
+    // because the direction is primitive
+    let scale = common_denominator(particular)?;
+    let offsets: Vec<i128> = particular
+        .iter()
+        .map(|value| value.numerator() * (scale / value.denominator()))
+        .collect();
+
+    // s * direction[i] = -offsets[i] (mod scale) for every i
+    let congruences: Option<Vec<(i128, i128)>> = offsets
+        .iter()
+        .zip(&direction)
+        .map(|(offset, step)| {
+            let divisor = gcd_i128(*step, scale);
+            if offset % divisor != 0 {
+                return None;
+            }
+            let modulus = scale / divisor;
+            let inverse = mod_inverse_i128(step / divisor, modulus)?;
+            Some(((-offset / divisor) * inverse, modulus))
+        })
+        .collect();
+    let (remainder, modulus) = congruences
+        .and_then(crt_i128)
+        .ok_or(CostError::NoSolution)?;
+
+    // x[i] >= 0 limits s from one side, depending on the sign of the direction
+    let mut lowest: Option<i128> = None;
+    let mut highest: Option<i128> = None;
+    for (offset, step) in offsets.iter().zip(&direction) {
+        match step.cmp(&0) {
+            Ordering::Equal if *offset < 0 => return Err(CostError::NoSolution),
+            Ordering::Equal => (),
+            Ordering::Greater => {
+                let bound = Rational::new(-offset, *step).ceil();
+                lowest = Some(lowest.map_or(bound, |lowest| lowest.max(bound)));
+            }
+            Ordering::Less => {
+                let bound = Rational::new(-offset, *step).floor();
+                highest = Some(highest.map_or(bound, |highest| highest.min(bound)));
+            }
+        }
+    }
+
+    // the cost is linear in s, so the cheapest point is at one of the ends
+    let slope: i128 = cost
+        .iter()
+        .zip(&direction)
+        .map(|(cost, step)| *cost as i128 * step)
+        .sum();
+    let s = match (slope.cmp(&0), lowest, highest) {
+        (Ordering::Greater | Ordering::Equal, Some(lowest), _) => {
+            lowest + (remainder - lowest).rem_euclid(modulus)
+        }
+        (Ordering::Less | Ordering::Equal, _, Some(highest)) => {
+            highest - (highest - remainder).rem_euclid(modulus)
+        }
+        (Ordering::Equal, None, None) => remainder,
+        // the cost gets lower forever
+        _ => return Err(CostError::NoSolution),
+    };
+    if lowest.is_some_and(|lowest| s < lowest) || highest.is_some_and(|highest| s > highest) {
+        return Err(CostError::NoSolution);
+    }
+
+    offsets
+        .iter()
+        .zip(&direction)
+        .map(|(offset, step)| i64::try_from((offset + s * step) / scale).ok())
+        .collect::<Option<Vec<i64>>>()
+        .ok_or(CostError::NoSolution)
+}
+
+/// Smallest integer vector in the same direction.
+fn primitive_direction(direction: &[Rational]) -> Result<Vec<i128>, CostError> {
+    let scale = common_denominator(direction)?;
+    let integers: Vec<i128> = direction
+        .iter()
+        .map(|value| value.numerator() * (scale / value.denominator()))
+        .collect();
+    let divisor = integers
+        .iter()
+        .fold(0, |divisor, value| gcd_i128(divisor, *value));
+    Ok(integers.iter().map(|value| value / divisor).collect())
+}
+
+fn common_denominator(values: &[Rational]) -> Result<i128, CostError> {
+    values
+        .iter()
+        .try_fold(1, |scale, value| {
+            checked_lcm_i128(scale, value.denominator())
+        })
+        .ok_or(CostError::Overflow)
+}
@@ -0,0 +1,205 @@
+#[test]
+pub fn test_math() {
+    assert_eq!(gcd(12, -18), 6);
+    assert_eq!(gcd(0, 0), 0);
+    assert_eq!(lcm(4, 6), 12);
+    assert_eq!(checked_lcm(i64::MAX, i64::MAX - 1), None);
+
+    let (g, x, y) = extended_gcd(240, 46);
+    assert_eq!(g, 2);
+    assert_eq!(240 * x + 46 * y, 2);
+    assert_eq!(mod_inverse(3, 11), Some(4));
+    assert_eq!(mod_inverse(2, 4), None);
+
+    assert_eq!(mod_pow(2, 10, 1000), 24);
+    assert_eq!(mod_pow(-2, 3, 5), 2);
+    assert_eq!(mul_mod(i64::MAX, i64::MAX, 1_000_000_007), 737564071);
+    // (-1) * (-1) without overflowing
+    assert_eq!(mul_mod_i128(i128::MAX - 1, i128::MAX - 1, i128::MAX), 1);
+    assert_eq!(mod_pow_i128(2, 127, i128::MAX), 1);
+
+    assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    // moduli do not need to be coprime, as long as the remainders agree
+    assert_eq!(crt([(2, 4), (4, 6)]), Some((10, 12)));
+    assert_eq!(crt([(1, 4), (2, 6)]), None);
+
+    assert_eq!(isqrt(15), 3);
+    assert_eq!(isqrt(16), 4);
+    assert_eq!(isqrt(u64::MAX), u32::MAX as u64);
+    assert_eq!(isqrt_u128(u128::MAX), u64::MAX as u128);
+}
+
+/// Greatest common divisor, always non-negative. gcd(0, 0) is 0.
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let mut a = a.abs();
+    let mut b = b.abs();
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub fn gcd_i128(a: i128, b: i128) -> i128 {
+    let mut a = a.abs();
+    let mut b = b.abs();
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, always non-negative. Panics if it does not fit into an i64.
+pub fn lcm(a: i64, b: i64) -> i64 {
+    checked_lcm(a, b).unwrap_or_else(|| panic!("lcm of {} and {} overflows.", a, b))
+}
+
+/// Least common multiple, None if it does not fit into an i64.
+pub fn checked_lcm(a: i64, b: i64) -> Option<i64> {
+    checked_lcm_i128(a as i128, b as i128).and_then(|lcm| i64::try_from(lcm).ok())
+}
+
+pub fn checked_lcm_i128(a: i128, b: i128) -> Option<i128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    // divide first, to keep the intermediate value small
+    (a / gcd_i128(a, b)).checked_mul(b)?.checked_abs()
+}
+
+/// Extended euclid: (g, x, y) with a * x + b * y = g = gcd(a, b).
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    // the coefficients are at most as big as the inputs, only the intermediate steps need more room
+    let (g, x, y) = extended_gcd_i128(a as i128, b as i128);
+    (g as i64, x as i64, y as i64)
+}
+
+pub fn extended_gcd_i128(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    // keep the gcd positive
+    if old_r < 0 {
+        return (-old_r, -old_x, -old_y);
+    }
+    (old_r, old_x, old_y)
+}
+
+/// x with a * x = 1 (mod m), in 0..m. None if a and m are not coprime.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    mod_inverse_i128(a as i128, m as i128).map(|x| x as i64)
+}
+
+pub fn mod_inverse_i128(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd_i128(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// a * b (mod m), in 0..m.
+pub fn mul_mod(a: i64, b: i64, m: i64) -> i64 {
+    (a as i128 * b as i128).rem_euclid(m as i128) as i64
+}
+
+/// a * b (mod m), in 0..m. Falls back to double and add, if the product does not fit into an i128.
+pub fn mul_mod_i128(a: i128, b: i128, m: i128) -> i128 {
+    let mut a = a.rem_euclid(m);
+    let mut b = b.rem_euclid(m);
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    let mut result = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod_i128(result, a, m);
+        }
+        a = add_mod_i128(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+/// a + b (mod m) for a and b in 0..m, without overflowing.
+fn add_mod_i128(a: i128, b: i128, m: i128) -> i128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+/// base ^ exp (mod m), in 0..m.
+pub fn mod_pow(base: i64, exp: u64, m: i64) -> i64 {
+    mod_pow_i128(base as i128, exp as u128, m as i128) as i64
+}
+
+pub fn mod_pow_i128(base: i128, exp: u128, m: i128) -> i128 {
+    let mut result = 1 % m;
+    let mut base = base.rem_euclid(m);
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod_i128(result, base, m);
+        }
+        base = mul_mod_i128(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Chinese remainder theorem for congruences x = remainder (mod modulus).
+/// Returns the combined (remainder, modulus), where the modulus is the lcm of all moduli.
+/// Moduli do not need to be coprime. None if the congruences contradict each other,
+/// or the combined modulus does not fit into an i64.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    let (remainder, modulus) = crt_i128(
+        congruences
+            .into_iter()
+            .map(|(remainder, modulus)| (remainder as i128, modulus as i128)),
+    )?;
+    Some((remainder as i64, i64::try_from(modulus).ok()?))
+}
+
+pub fn crt_i128(congruences: impl IntoIterator<Item = (i128, i128)>) -> Option<(i128, i128)> {
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(r1, m1), (r2, m2)| {
+            let r2 = r2.rem_euclid(m2);
+            let (g, p, _) = extended_gcd_i128(m1, m2);
+            let difference = r2 - r1;
+            if difference % g != 0 {
+                return None;
+            }
+            // x = r1 + m1 * t, with m1 * t = r2 - r1 (mod m2)
+            let step = m2 / g;
+            let t = mul_mod_i128(difference / g, p, step);
+            let modulus = checked_lcm_i128(m1, m2)?;
+            // m1 * t < lcm, so it fits, if the lcm fits
+            Some(((r1 + m1 * t).rem_euclid(modulus), modulus))
+        })
+}
+
+/// Integer square root, rounded down.
+pub fn isqrt(n: u64) -> u64 {
+    isqrt_u128(n as u128) as u64
+}
+
+pub fn isqrt_u128(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // newton, starting above the root, so it only goes down
+    let bits = 128 - n.leading_zeros();
+    let mut x: u128 = 1 << bits.div_ceil(2);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
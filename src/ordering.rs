@@ -0,0 +1,266 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
+use crate::graph::Graph;
+
+#[test]
+pub fn test_rules() {
+    let rules = Rules::from_pairs([(47, 53), (97, 13), (97, 61), (61, 13), (53, 13), (61, 53)]);
+
+    assert!(rules.must_precede(&97, &13));
+    assert!(!rules.must_precede(&13, &97));
+    assert!(rules.violations(&[97, 61, 53, 13]).is_empty());
+    assert_eq!(rules.violations(&[61, 97, 13]), vec![(97, 61)]);
+
+    // 47 and 97 have no rule between each other, so the order is only partial.
+    // Unrelated items keep their given order.
+    assert_eq!(
+        rules.sort(&[13, 53, 47, 61, 97]),
+        Ok(vec![47, 97, 61, 53, 13])
+    );
+    assert!(!rules.is_unique_order(&[47, 97, 61, 53, 13]));
+    assert!(rules.is_unique_order(&[97, 61, 53, 13]));
+
+    assert_eq!(rules.contradictions(), vec![]);
+    assert_eq!(rules.find_cycle(), None);
+}
+
+#[test]
+pub fn test_rule_cycles() {
+    let rules = Rules::from_pairs([(1, 2), (2, 3), (3, 1), (3, 4), (4, 3)]);
+
+    assert_eq!(rules.contradictions(), vec![(3, 4)]);
+    let cycle = rules.find_cycle().unwrap();
+    assert!(cycle.len() >= 2);
+    // the whole rule set is cyclic, but a subset can still be sortable
+    assert_eq!(rules.sort(&[2, 1]), Ok(vec![1, 2]));
+    assert!(matches!(rules.sort(&[3, 1, 2]), Err(OrderError::Cycle(_))));
+}
+
+/// Ordering rules of the form "a must come before b", e.g. page ordering rules `47|53`.
+/// The rules build a dependency graph, which does not need to be a total order or even acyclic as a whole.
+/// Sorting only looks at the rules between the given items.
+#[derive(Debug, Clone)]
+pub struct Rules<T> {
+    graph: Graph<T>,
+}
+
+/// Why a set of items cannot be ordered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderError<T> {
+    /// The rules between the items form a cycle, listed in rule order.
+    Cycle(Vec<T>),
+}
+
+impl<T: Debug> Display for OrderError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderError::Cycle(items) => write!(f, "Rules contain a cycle: {:?}", items),
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone> Default for Rules<T> {
+    fn default() -> Self {
+        Self {
+            graph: Graph::new(),
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone> Rules<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Each pair (a, b) means a must come before b.
+    pub fn from_pairs(pairs: impl IntoIterator<Item = (T, T)>) -> Self {
+        let mut rules = Self::new();
+        pairs
+            .into_iter()
+            .for_each(|(before, after)| rules.add(before, after));
+        rules
+    }
+
+    pub fn add(&mut self, before: T, after: T) {
+        self.graph.add_edge(before, after, 1);
+    }
+
+    /// The dependency graph, with an edge from each item to the items that must come after it.
+    pub fn graph(&self) -> &Graph<T> {
+        &self.graph
+    }
+
+    /// Is there a direct rule, that a must come before b.
+    pub fn must_precede(&self, a: &T, b: &T) -> bool {
+        self.graph.edge_weight(a, b).is_some()
+    }
+
+    /// Pairs, for which both directions are a rule. Each pair is listed once, in the order of the first rule.
+    pub fn contradictions(&self) -> Vec<(T, T)> {
+        self.graph
+            .edges()
+            .filter(|(from, edge)| {
+                *from < edge.to
+                    && self
+                        .graph
+                        .successors(edge.to)
+                        .iter()
+                        .any(|back| back.to == *from)
+            })
+            .map(|(from, edge)| {
+                (
+                    self.graph.node(from).clone(),
+                    self.graph.node(edge.to).clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Any cycle within all rules, in rule order. None, if all rules together form a partial order.
+    pub fn find_cycle(&self) -> Option<Vec<T>> {
+        let all: Vec<usize> = (0..self.graph.len()).collect();
+        self.find_cycle_within(&all)
+    }
+
+    /// All rules (a, b), that the order breaks, because b comes before a. Items without rules are ignored.
+    pub fn violations(&self, order: &[T]) -> Vec<(T, T)> {
+        let positions: HashMap<usize, usize> = order
+            .iter()
+            .enumerate()
+            .filter_map(|(position, item)| self.graph.index_of(item).map(|index| (index, position)))
+            .collect();
+
+        let mut violations = Vec::new();
+        order.iter().for_each(|item| {
+            let Some(index) = self.graph.index_of(item) else {
+                return;
+            };
+            self.graph.successors(index).iter().for_each(|edge| {
+                if positions
+                    .get(&edge.to)
+                    .is_some_and(|after| *after < positions[&index])
+                {
+                    violations.push((item.clone(), self.graph.node(edge.to).clone()));
+                }
+            });
+        });
+        violations
+    }
+
+    /// Topological sort of the items, only using the rules between them.
+    /// Whenever several items are possible, the one that came first in the input is taken, so the result is stable.
+    pub fn sort(&self, items: &[T]) -> Result<Vec<T>, OrderError<T>> {
+        let subset = self.subset(items);
+        // items can be listed more than once, so every graph index can have multiple positions
+        let mut positions: HashMap<usize, Vec<usize>> = HashMap::new();
+        subset.iter().enumerate().for_each(|(position, index)| {
+            if let Some(index) = index {
+                positions.entry(*index).or_default().push(position);
+            }
+        });
+        let positions_of = |index: usize| positions.get(&index).into_iter().flatten().copied();
+
+        // count incoming rules from within the subset
+        let mut incoming: Vec<usize> = vec![0; items.len()];
+        subset.iter().flatten().for_each(|index| {
+            self.graph.successors(*index).iter().for_each(|edge| {
+                positions_of(edge.to).for_each(|after| incoming[after] += 1);
+            });
+        });
+
+        let mut available: BinaryHeap<Reverse<usize>> = (0..items.len())
+            .filter(|position| incoming[*position] == 0)
+            .map(Reverse)
+            .collect();
+        let mut sorted = Vec::new();
+        while let Some(Reverse(position)) = available.pop() {
+            sorted.push(items[position].clone());
+            if let Some(index) = subset[position] {
+                self.graph.successors(index).iter().for_each(|edge| {
+                    positions_of(edge.to).for_each(|after| {
+                        incoming[after] -= 1;
+                        if incoming[after] == 0 {
+                            available.push(Reverse(after));
+                        }
+                    });
+                });
+            }
+        }
+
+        if sorted.len() < items.len() {
+            let indices: Vec<usize> = subset.iter().flatten().copied().collect();
+            let cycle = self
+                .find_cycle_within(&indices)
+                .expect("Items are left over, so there must be a cycle.");
+            return Err(OrderError::Cycle(cycle));
+        }
+        Ok(sorted)
+    }
+
+    /// Do the rules between the items allow only this one order. That is the case,
+    /// if every neighbouring pair is connected by a rule, otherwise the pair could be swapped.
+    pub fn is_unique_order(&self, order: &[T]) -> bool {
+        order
+            .windows(2)
+            .all(|window| self.must_precede(&window[0], &window[1]))
+    }
+
+    /// Graph index of every item, None for items without any rule.
+    fn subset(&self, items: &[T]) -> Vec<Option<usize>> {
+        items.iter().map(|item| self.graph.index_of(item)).collect()
+    }
+
+    /// Depth first search for a back edge, only following edges between the given nodes.
+    fn find_cycle_within(&self, indices: &[usize]) -> Option<Vec<T>> {
+        let allowed: HashSet<usize> = indices.iter().copied().collect();
+        // no entry = not visited, 1 = on the current path, 2 = done
+        let mut state: HashMap<usize, u8> = HashMap::new();
+
+        for start in indices {
+            if state.contains_key(start) {
+                continue;
+            }
+            // every entry is a node and the position of the next edge to check
+            let mut path: Vec<(usize, usize)> = vec![(*start, 0)];
+            state.insert(*start, 1);
+            while let Some((node, edge_position)) = path.last_mut() {
+                let node = *node;
+                let Some(edge) = self.graph.successors(node).get(*edge_position) else {
+                    state.insert(node, 2);
+                    path.pop();
+                    continue;
+                };
+                *edge_position += 1;
+                if !allowed.contains(&edge.to) {
+                    continue;
+                }
+                match state.get(&edge.to) {
+                    Some(1) => {
+                        // back edge, the cycle is the path from that node on
+                        let cycle_start = path
+                            .iter()
+                            .position(|(index, _)| *index == edge.to)
+                            .unwrap_or(0);
+                        return Some(
+                            path[cycle_start..]
+                                .iter()
+                                .map(|(index, _)| self.graph.node(*index).clone())
+                                .collect(),
+                        );
+                    }
+                    Some(_) => (),
+                    None => {
+                        state.insert(edge.to, 1);
+                        path.push((edge.to, 0));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,90 @@
+use std::str::FromStr;
+
+pub use puzzle_parse_derive::PuzzleParse;
+
+use crate::{input, scan::ScanError};
+
+#[test]
+pub fn test_puzzle_parse() {
+    #[derive(Debug, PartialEq, PuzzleParse)]
+    #[parse("{name}: {x},{y}")]
+    struct Named {
+        name: String,
+        x: i64,
+        y: i64,
+    }
+
+    #[derive(Debug, PartialEq, PuzzleParse)]
+    #[parse("{}|{}")]
+    struct Pair(u32, u32);
+
+    #[derive(Debug, PartialEq, PuzzleParse)]
+    #[parse("A={a}\nB={b}")]
+    struct Block {
+        a: Vec<u8>,
+        b: Option<char>,
+    }
+
+    assert_eq!(
+        "start: 3,-4".parse(),
+        Ok(Named {
+            name: "start".to_string(),
+            x: 3,
+            y: -4
+        })
+    );
+    assert_eq!(
+        Pair::parse_lines("1|2\n\n3|4\n"),
+        Ok(vec![Pair(1, 2), Pair(3, 4)])
+    );
+    assert_eq!(
+        Block::parse_sections("A=1,2\nB=x\n\nA=\nB=").unwrap(),
+        vec![
+            Block {
+                a: vec![1, 2],
+                b: Some('x')
+            },
+            Block { a: vec![], b: None }
+        ]
+    );
+
+    // errors point to the line in the whole input
+    let error = Pair::parse_lines("1|2\n3|x").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 2, column 3: field #1 \"x\" is invalid: invalid digit found in string"
+    );
+    let error = Block::parse_sections("A=1\nB=x\n\nA=1\nC=2").unwrap_err();
+    assert_eq!((error.line, error.column), (4, 3));
+}
+
+/// Records of a puzzle input, usually derived with `#[derive(PuzzleParse)]` and a `#[parse("...")]` template.
+pub trait PuzzleParse: FromStr<Err = ScanError> {
+    /// One record per line, empty lines are skipped.
+    fn parse_lines(input: &str) -> Result<Vec<Self>, ScanError> {
+        input::lines(input)
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(index, line)| {
+                line.parse().map_err(|mut error: ScanError| {
+                    error.line += index;
+                    error
+                })
+            })
+            .collect()
+    }
+
+    /// One record per block of lines, blocks are separated by empty lines.
+    fn parse_sections(input: &str) -> Result<Vec<Self>, ScanError> {
+        input::sections(input)
+            .map(|section| {
+                section.parse().map_err(|mut error: ScanError| {
+                    // sections are slices of the input, so their offset tells the line they start at
+                    let offset = section.as_ptr() as usize - input.as_ptr() as usize;
+                    error.line += input[..offset].matches('\n').count();
+                    error
+                })
+            })
+            .collect()
+    }
+}
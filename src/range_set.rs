@@ -0,0 +1,344 @@
+use std::{collections::BTreeMap, ops::Range};
+
+#[test]
+pub fn test_range_set() {
+    let mut set: RangeSet = [0..2, 5..7, 2..3].into_iter().collect();
+    // touching ranges are coalesced
+    assert_eq!(set.ranges().collect::<Vec<_>>(), vec![0..3, 5..7]);
+    assert_eq!(set.covered_len(), 5);
+
+    set.insert(3..5);
+    assert_eq!(set.ranges().collect::<Vec<_>>(), vec![0..7]);
+
+    set.remove(2..4);
+    assert_eq!(set.ranges().collect::<Vec<_>>(), vec![0..2, 4..7]);
+    assert!(set.contains(1));
+    assert!(!set.contains(2));
+    assert_eq!(set.range_containing(5), Some(4..7));
+
+    assert_eq!(set.first_fit(2), Some(0..2));
+    assert_eq!(set.first_fit(3), Some(4..7));
+    assert_eq!(set.first_fit(4), None);
+
+    let right = set.split_off(5);
+    assert_eq!(set.ranges().collect::<Vec<_>>(), vec![0..2, 4..5]);
+    assert_eq!(right.ranges().collect::<Vec<_>>(), vec![5..7]);
+    assert_eq!(set.first_fit(2), Some(0..2));
+    assert_eq!(set.first_fit(3), None);
+
+    // many different lengths, the leftmost long enough range wins
+    let set: RangeSet = (0..100).map(|i| i * 200..i * 200 + i % 17).collect();
+    for length in 1..17 {
+        let expected = set.ranges().find(|range| range.end - range.start >= length);
+        assert_eq!(set.first_fit(length), expected);
+    }
+    assert_eq!(set.first_fit(17), None);
+}
+
+#[test]
+pub fn test_range_set_operations() {
+    let a: RangeSet = [0..5, 10..15].into_iter().collect();
+    let b: RangeSet = [3..12, 20..21].into_iter().collect();
+
+    assert_eq!(
+        a.union(&b).ranges().collect::<Vec<_>>(),
+        vec![0..15, 20..21]
+    );
+    assert_eq!(
+        a.intersection(&b).ranges().collect::<Vec<_>>(),
+        vec![3..5, 10..12]
+    );
+    assert_eq!(
+        a.difference(&b).ranges().collect::<Vec<_>>(),
+        vec![0..3, 12..15]
+    );
+    assert!(a.difference(&a).is_empty());
+}
+
+/// Set of integers, stored as sorted, disjoint half-open ranges. Overlapping or touching ranges are
+/// always coalesced, so there is exactly one way to store every set.
+/// Insert, remove, contains and first fit are logarithmic in the number of ranges.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeSet {
+    // start -> end
+    ranges: BTreeMap<u64, u64>,
+    // the same ranges with their lengths, for first fit lookups
+    lengths: LengthTree,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of disjoint ranges.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of integers in the set.
+    pub fn covered_len(&self) -> u64 {
+        self.ranges.iter().map(|(start, end)| end - start).sum()
+    }
+
+    /// All ranges in ascending order.
+    pub fn ranges(&self) -> impl Iterator<Item = Range<u64>> + '_ {
+        self.ranges.iter().map(|(start, end)| *start..*end)
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        self.range_containing(value).is_some()
+    }
+
+    /// The stored range, that contains the value.
+    pub fn range_containing(&self, value: u64) -> Option<Range<u64>> {
+        self.ranges
+            .range(..=value)
+            .next_back()
+            .filter(|(_, end)| **end > value)
+            .map(|(start, end)| *start..*end)
+    }
+
+    /// Add the range, and coalesce it with all ranges it overlaps or touches.
+    pub fn insert(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return;
+        }
+        let mut start = range.start;
+        let mut end = range.end;
+        // ranges are disjoint, so their ends are sorted as well and walking backwards can stop at the first miss
+        let merged: Vec<(u64, u64)> = self
+            .ranges
+            .range(..=range.end)
+            .rev()
+            .take_while(|(_, other_end)| **other_end >= range.start)
+            .map(|(start, end)| (*start, *end))
+            .collect();
+        for (other_start, other_end) in merged {
+            self.remove_stored(other_start);
+            start = start.min(other_start);
+            end = end.max(other_end);
+        }
+        self.insert_stored(start, end);
+    }
+
+    /// Remove the range, the ranges it cuts are split.
+    pub fn remove(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return;
+        }
+        let cut: Vec<(u64, u64)> = self
+            .ranges
+            .range(..range.end)
+            .rev()
+            .take_while(|(_, other_end)| **other_end > range.start)
+            .map(|(start, end)| (*start, *end))
+            .collect();
+        for (other_start, other_end) in cut {
+            self.remove_stored(other_start);
+            if other_start < range.start {
+                self.insert_stored(other_start, range.start);
+            }
+            if other_end > range.end {
+                self.insert_stored(range.end, other_end);
+            }
+        }
+    }
+
+    /// Split the set at the value, everything from the value on is moved into the returned set.
+    pub fn split_off(&mut self, at: u64) -> RangeSet {
+        if let Some(range) = self.range_containing(at) {
+            if range.start < at {
+                self.remove_stored(range.start);
+                self.insert_stored(range.start, at);
+                self.insert_stored(at, range.end);
+            }
+        }
+        let mut right = RangeSet::new();
+        let moved: Vec<(u64, u64)> = self
+            .ranges
+            .range(at..)
+            .map(|(start, end)| (*start, *end))
+            .collect();
+        for (start, end) in moved {
+            self.remove_stored(start);
+            right.insert_stored(start, end);
+        }
+        right
+    }
+
+    /// The lowest range, that is at least length long.
+    pub fn first_fit(&self, length: u64) -> Option<Range<u64>> {
+        self.lengths
+            .first_fit(length.max(1))
+            .map(|start| start..self.ranges[&start])
+    }
+
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        let mut union = self.clone();
+        other.ranges().for_each(|range| union.insert(range));
+        union
+    }
+
+    pub fn difference(&self, other: &RangeSet) -> RangeSet {
+        let mut difference = self.clone();
+        other.ranges().for_each(|range| difference.remove(range));
+        difference
+    }
+
+    pub fn intersection(&self, other: &RangeSet) -> RangeSet {
+        let mut intersection = RangeSet::new();
+        let mut left = self.ranges().peekable();
+        let mut right = other.ranges().peekable();
+        while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                intersection.insert_stored(start, end);
+            }
+            // drop the range, that ends first, it cannot overlap anything else
+            if a.end < b.end {
+                left.next();
+            } else {
+                right.next();
+            }
+        }
+        intersection
+    }
+
+    // both maps have to be changed together, the callers keep the ranges disjoint and not touching
+    fn insert_stored(&mut self, start: u64, end: u64) {
+        self.ranges.insert(start, end);
+        self.lengths.insert(start, end - start);
+    }
+
+    fn remove_stored(&mut self, start: u64) {
+        self.ranges.remove(&start).expect("Range is not stored.");
+        self.lengths.remove(start);
+    }
+}
+
+impl FromIterator<Range<u64>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Range<u64>>>(iter: I) -> Self {
+        let mut set = RangeSet::new();
+        iter.into_iter().for_each(|range| set.insert(range));
+        set
+    }
+}
+
+/// Treap of the ranges by their start, where every node knows the longest range below it.
+/// So the lowest range with a minimum length is found on a single path down from the root.
+/// Priorities are a hash of the start, so the same ranges always build the same tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct LengthTree {
+    root: Option<Box<Node>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Node {
+    start: u64,
+    length: u64,
+    priority: u64,
+    // longest length in the subtree of this node
+    max_length: u64,
+    left: Option<Box<Node>>,
+    right: Option<Box<Node>>,
+}
+
+impl LengthTree {
+    fn insert(&mut self, start: u64, length: u64) {
+        let (left, right) = split(self.root.take(), start);
+        let node = Box::new(Node {
+            start,
+            length,
+            priority: priority(start),
+            max_length: length,
+            left: None,
+            right: None,
+        });
+        self.root = merge(merge(left, Some(node)), right);
+    }
+
+    fn remove(&mut self, start: u64) {
+        let (left, right) = split(self.root.take(), start);
+        // the removed node is the only one with exactly this start, stored ranges are never empty
+        let (_, right) = split(right, start + 1);
+        self.root = merge(left, right);
+    }
+
+    /// Start of the lowest range, that is at least length long.
+    fn first_fit(&self, length: u64) -> Option<u64> {
+        let mut node = self.root.as_deref()?;
+        if node.max_length < length {
+            return None;
+        }
+        loop {
+            match &node.left {
+                Some(left) if left.max_length >= length => node = left,
+                _ if node.length >= length => return Some(node.start),
+                // the subtree is long enough, and neither the left side nor the node itself are
+                _ => node = node.right.as_deref()?,
+            }
+        }
+    }
+}
+
+fn max_length(node: &Option<Box<Node>>) -> u64 {
+    node.as_ref().map_or(0, |node| node.max_length)
+}
+
+fn update(node: &mut Node) {
+    node.max_length = node
+        .length
+        .max(max_length(&node.left))
+        .max(max_length(&node.right));
+}
+
+/// Nodes with a start below the key, and all others.
+fn split(node: Option<Box<Node>>, key: u64) -> (Option<Box<Node>>, Option<Box<Node>>) {
+    let Some(mut node) = node else {
+        return (None, None);
+    };
+    if node.start < key {
+        let (left, right) = split(node.right.take(), key);
+        node.right = left;
+        update(&mut node);
+        (Some(node), right)
+    } else {
+        let (left, right) = split(node.left.take(), key);
+        node.left = right;
+        update(&mut node);
+        (left, Some(node))
+    }
+}
+
+/// All starts in left have to be below the ones in right.
+fn merge(left: Option<Box<Node>>, right: Option<Box<Node>>) -> Option<Box<Node>> {
+    match (left, right) {
+        (None, right) => right,
+        (left, None) => left,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.right = merge(left.right.take(), Some(right));
+                update(&mut left);
+                Some(left)
+            } else {
+                right.left = merge(Some(left), right.left.take());
+                update(&mut right);
+                Some(right)
+            }
+        }
+    }
+}
+
+/// splitmix64, a bijection, so different starts never get the same priority.
+fn priority(start: u64) -> u64 {
+    let mut z = start.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
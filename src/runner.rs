@@ -0,0 +1,83 @@
+use std::{
+    fmt::Display,
+    panic::{catch_unwind, UnwindSafe},
+    time::{Duration, Instant},
+};
+
+use crate::arith::Overflow;
+
+#[test]
+pub fn test_runner() {
+    assert!(run(|| ()).is_ok());
+
+    let result = run(|| std::panic::panic_any(Overflow::new::<u64>("*", 1_u64 << 63, 2)));
+    assert_eq!(
+        result,
+        Err(RunError::Overflow(Overflow::new::<u64>(
+            "*",
+            1_u64 << 63,
+            2
+        )))
+    );
+
+    let result = run(|| assert_eq!(1, 2, "wrong answer"));
+    assert!(matches!(result, Err(RunError::Panic(message)) if message.contains("wrong answer")));
+}
+
+#[cfg(feature = "checked-arithmetic")]
+#[test]
+pub fn test_report_overflow() {
+    let result = report("overflow", || {
+        crate::arith::mul(u64::MAX, 2);
+    });
+    assert_eq!(
+        result,
+        Err(RunError::Overflow(Overflow::new::<u64>("*", u64::MAX, 2)))
+    );
+}
+
+/// Why a puzzle run failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunError {
+    /// Arithmetic overflowed, only detected with the checked-arithmetic feature.
+    Overflow(Overflow),
+    /// Any other panic, e.g. a failed answer assertion.
+    Panic(String),
+}
+
+impl Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunError::Overflow(overflow) => write!(f, "{}", overflow),
+            RunError::Panic(message) => write!(f, "panic: {}", message),
+        }
+    }
+}
+
+/// Run a puzzle and catch its panics, so overflows are reported as errors instead of wrong answers.
+/// Returns the run time.
+pub fn run(puzzle: impl FnOnce() + UnwindSafe) -> Result<Duration, RunError> {
+    let start = Instant::now();
+    catch_unwind(puzzle).map_err(|payload| {
+        if let Some(overflow) = payload.downcast_ref::<Overflow>() {
+            return RunError::Overflow(overflow.clone());
+        }
+        let message = payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_default();
+        RunError::Panic(message)
+    })?;
+    Ok(start.elapsed())
+}
+
+/// Run a puzzle and print how it went.
+pub fn report(name: &str, puzzle: impl FnOnce() + UnwindSafe) -> Result<Duration, RunError> {
+    let result = run(puzzle);
+    match &result {
+        Ok(duration) => println!("{} finished in {:?}", name, duration),
+        Err(error) => eprintln!("{} failed: {}", name, error),
+    }
+    result
+}
@@ -0,0 +1,461 @@
+use std::fmt::Display;
+
+#[test]
+pub fn test_scan() {
+    let (button, x, y): (char, i64, i64) =
+        crate::scan!("Button {}: X+{}, Y+{}", "Button A: X+94, Y+34").unwrap();
+    assert_eq!((button, x, y), ('A', 94, 34));
+
+    let scanned = scan(
+        "{name} is {age} years, likes {}",
+        "Bob is 42 years, likes 1, 2 3",
+    )
+    .unwrap();
+    assert_eq!(scanned.len(), 3);
+    assert_eq!(scanned.named::<String>("name").unwrap(), "Bob");
+    assert_eq!(scanned.named::<u32>("age").unwrap(), 42);
+    assert_eq!(scanned.get::<Vec<u8>>(2).unwrap(), vec![1, 2, 3]);
+
+    let (a, b): (Option<i32>, Option<i32>) = crate::scan!("[{}|{}]", "[|7]").unwrap();
+    assert_eq!((a, b), (None, Some(7)));
+
+    let (literal,): (String,) = crate::scan!("{{{}}}", "{x}").unwrap();
+    assert_eq!(literal, "x");
+
+    let robots: Vec<(i64, i64)> = scan_all("p={},{}", "p=0,4\np=6,-3\n\np=10,3\n").unwrap();
+    assert_eq!(robots, vec![(0, 4), (6, -3), (10, 3)]);
+
+    // a newline of the template matches CRLF, which is never part of a field
+    let (a, b): (u32, String) = crate::scan!("A={}\nB={}", "A=1\r\nB=x\r\n").unwrap();
+    assert_eq!((a, b.as_str()), (1, "x"));
+}
+
+#[test]
+pub fn test_scan_errors() {
+    let error = scan("X+{}, Y+{}", "X+94; Y+34").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 1, column 3: expected \", Y+\", found \"94; Y+34\""
+    );
+
+    let result: Result<(i64, i64), ScanError> = crate::scan!("X+{}, Y+{}", "X+94, Y+abc");
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "line 1, column 9: field #1 \"abc\" is invalid: invalid digit found in string"
+    );
+
+    let result: Result<(i64,), ScanError> = crate::scan!("X+{}, Y+{}", "X+94, Y+34");
+    assert_eq!(
+        result.unwrap_err().kind,
+        ScanErrorKind::FieldCount {
+            expected: 1,
+            found: 2
+        }
+    );
+
+    let result: Result<Vec<(i64, i64)>, ScanError> = scan_all("p={},{}", "p=0,4\np=6;3");
+    assert_eq!(result.unwrap_err().line, 2);
+}
+
+/// Why an input does not fit a template. Line and column are 1-based and point into the scanned input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ScanErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanErrorKind {
+    /// Literal text of the template is missing, found is the rest of the line.
+    MissingLiteral { expected: String, found: String },
+    /// There is more input after the template was matched completely.
+    TrailingInput { rest: String },
+    /// A field could not be converted into the requested type.
+    InvalidField {
+        field: String,
+        value: String,
+        message: String,
+    },
+    /// Different count of fields in the template and the requested tuple.
+    FieldCount { expected: usize, found: usize },
+}
+
+impl ScanError {
+    fn at(input: &str, position: usize, kind: ScanErrorKind) -> Self {
+        let before = &input[..position];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            kind,
+        }
+    }
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+impl Display for ScanErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanErrorKind::MissingLiteral { expected, found } => {
+                write!(f, "expected {:?}, found {:?}", expected, found)
+            }
+            ScanErrorKind::TrailingInput { rest } => write!(f, "unexpected {:?} at the end", rest),
+            ScanErrorKind::InvalidField {
+                field,
+                value,
+                message,
+            } => write!(f, "field {} {:?} is invalid: {}", field, value, message),
+            ScanErrorKind::FieldCount { expected, found } => {
+                write!(f, "expected {} fields, template has {}", expected, found)
+            }
+        }
+    }
+}
+
+/// Types, that a single field of a template can be converted into.
+pub trait ScanField: Sized {
+    fn scan_field(value: &str) -> Result<Self, String>;
+}
+
+macro_rules! impl_scan_field {
+    ($($t:ty),*) => {
+        $(
+            impl ScanField for $t {
+                fn scan_field(value: &str) -> Result<Self, String> {
+                    value.trim().parse().map_err(|error| format!("{}", error))
+                }
+            }
+        )*
+    };
+}
+
+impl_scan_field!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f64);
+
+impl ScanField for char {
+    fn scan_field(value: &str) -> Result<Self, String> {
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => Ok(ch),
+            _ => Err("expected a single character".to_string()),
+        }
+    }
+}
+
+impl ScanField for String {
+    fn scan_field(value: &str) -> Result<Self, String> {
+        Ok(value.to_string())
+    }
+}
+
+/// An empty field is None.
+impl<T: ScanField> ScanField for Option<T> {
+    fn scan_field(value: &str) -> Result<Self, String> {
+        if value.trim().is_empty() {
+            return Ok(None);
+        }
+        T::scan_field(value).map(Some)
+    }
+}
+
+/// Items separated by commas or whitespace, e.g. "1, 2 3".
+impl<T: ScanField> ScanField for Vec<T> {
+    fn scan_field(value: &str) -> Result<Self, String> {
+        value
+            .split(|ch: char| ch == ',' || ch.is_whitespace())
+            .filter(|item| !item.is_empty())
+            .map(T::scan_field)
+            .collect()
+    }
+}
+
+/// Tuples of fields, so a whole template can be converted at once.
+pub trait FromFields: Sized {
+    fn from_fields(scanned: &Scanned) -> Result<Self, ScanError>;
+}
+
+macro_rules! impl_from_fields {
+    ($len:expr; $($t:ident $index:tt),*) => {
+        impl<$($t: ScanField),*> FromFields for ($($t,)*) {
+            fn from_fields(scanned: &Scanned) -> Result<Self, ScanError> {
+                if scanned.len() != $len {
+                    return Err(ScanError::at(
+                        scanned.input,
+                        scanned.start,
+                        ScanErrorKind::FieldCount {
+                            expected: $len,
+                            found: scanned.len(),
+                        },
+                    ));
+                }
+                Ok(($(scanned.get::<$t>($index)?,)*))
+            }
+        }
+    };
+}
+
+impl_from_fields!(1; A 0);
+impl_from_fields!(2; A 0, B 1);
+impl_from_fields!(3; A 0, B 1, C 2);
+impl_from_fields!(4; A 0, B 1, C 2, D 3);
+impl_from_fields!(5; A 0, B 1, C 2, D 3, E 4);
+impl_from_fields!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+impl_from_fields!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_from_fields!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part<'t> {
+    Literal(String),
+    // optional name of the field
+    Field(Option<&'t str>),
+}
+
+/// A parsed template like "Button {}: X+{}, Y+{}". Every {} or {name} is a field, {{ and }} are literal braces.
+/// A field ends where the following literal text starts, a field at the end takes the rest of the line.
+/// Fields never span lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template<'t> {
+    parts: Vec<Part<'t>>,
+}
+
+impl<'t> Template<'t> {
+    /// Panics on unbalanced braces or two fields without literal text between them, as there is no way to split them.
+    pub fn new(template: &'t str) -> Self {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template;
+        while let Some(ch) = rest.chars().next() {
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                literal.push(ch);
+                rest = &rest[2..];
+                continue;
+            }
+            if ch == '{' {
+                let end = rest
+                    .find('}')
+                    .unwrap_or_else(|| panic!("Unclosed field in template {:?}.", template));
+                if !literal.is_empty() {
+                    parts.push(Part::Literal(std::mem::take(&mut literal)));
+                }
+                assert!(
+                    !matches!(parts.last(), Some(Part::Field(_))),
+                    "Two fields without text between them in template {:?}.",
+                    template
+                );
+                let name = &rest[1..end];
+                parts.push(Part::Field((!name.is_empty()).then_some(name)));
+                rest = &rest[end + 1..];
+                continue;
+            }
+            assert!(ch != '}', "Unopened field in template {:?}.", template);
+            literal.push(ch);
+            rest = &rest[ch.len_utf8()..];
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Self { parts }
+    }
+
+    /// Match the whole input, only trailing whitespace is allowed.
+    pub fn scan<'a>(&self, input: &'a str) -> Result<Scanned<'a>, ScanError>
+    where
+        't: 'a,
+    {
+        let scanned = self.scan_at(input, 0)?;
+        let rest = &input[scanned.end..];
+        if !rest.trim().is_empty() {
+            return Err(ScanError::at(
+                input,
+                scanned.end,
+                ScanErrorKind::TrailingInput {
+                    rest: rest.to_string(),
+                },
+            ));
+        }
+        Ok(scanned)
+    }
+
+    /// Match the template over and over, records may be separated by whitespace like empty lines.
+    pub fn scan_all<T: FromFields>(&self, input: &str) -> Result<Vec<T>, ScanError> {
+        let mut records = Vec::new();
+        let mut position = 0;
+        loop {
+            position = input.len() - input[position..].trim_start().len();
+            if position == input.len() {
+                return Ok(records);
+            }
+            let scanned = self.scan_at(input, position)?;
+            records.push(scanned.parse()?);
+            position = scanned.end;
+        }
+    }
+
+    fn scan_at<'a>(&self, input: &'a str, start: usize) -> Result<Scanned<'a>, ScanError>
+    where
+        't: 'a,
+    {
+        let mut fields = Vec::new();
+        let mut position = start;
+        for (index, part) in self.parts.iter().enumerate() {
+            let rest = &input[position..];
+            let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            match part {
+                Part::Literal(literal) => {
+                    let Some(len) = match_literal(rest, literal) else {
+                        return Err(ScanError::at(
+                            input,
+                            position,
+                            ScanErrorKind::MissingLiteral {
+                                expected: literal.clone(),
+                                found: line.to_string(),
+                            },
+                        ));
+                    };
+                    position += len;
+                }
+                Part::Field(name) => {
+                    let len = match self.parts.get(index + 1) {
+                        Some(Part::Literal(next)) => (0..=line.len())
+                            .filter(|len| rest.is_char_boundary(*len))
+                            .find(|len| match_literal(&rest[*len..], next).is_some())
+                            .ok_or_else(|| {
+                                ScanError::at(
+                                    input,
+                                    position,
+                                    ScanErrorKind::MissingLiteral {
+                                        expected: next.clone(),
+                                        found: line.to_string(),
+                                    },
+                                )
+                            })?,
+                        _ => line.len(),
+                    };
+                    fields.push(Field {
+                        name: *name,
+                        value: &rest[..len],
+                        position,
+                    });
+                    position += len;
+                }
+            }
+        }
+        Ok(Scanned {
+            input,
+            start,
+            end: position,
+            fields,
+        })
+    }
+}
+
+/// Length of the literal at the start of the input. A newline of the template matches CRLF as well.
+fn match_literal(input: &str, literal: &str) -> Option<usize> {
+    let mut position = 0;
+    for ch in literal.chars() {
+        let rest = &input[position..];
+        if ch == '\n' && rest.starts_with("\r\n") {
+            position += 2;
+        } else if rest.starts_with(ch) {
+            position += ch.len_utf8();
+        } else {
+            return None;
+        }
+    }
+    Some(position)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Field<'a> {
+    name: Option<&'a str>,
+    value: &'a str,
+    // byte position in the input, for errors
+    position: usize,
+}
+
+/// The raw fields of an input, that matched a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scanned<'a> {
+    input: &'a str,
+    start: usize,
+    end: usize,
+    fields: Vec<Field<'a>>,
+}
+
+impl<'a> Scanned<'a> {
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// The unconverted text of a field.
+    pub fn raw(&self, index: usize) -> &'a str {
+        self.fields[index].value
+    }
+
+    /// Convert the field at the index. Panics, if there is no such field.
+    pub fn get<T: ScanField>(&self, index: usize) -> Result<T, ScanError> {
+        let field = &self.fields[index];
+        T::scan_field(field.value).map_err(|message| {
+            let name = field
+                .name
+                .map_or_else(|| format!("#{}", index), |name| format!("{{{}}}", name));
+            ScanError::at(
+                self.input,
+                field.position,
+                ScanErrorKind::InvalidField {
+                    field: name,
+                    value: field.value.to_string(),
+                    message,
+                },
+            )
+        })
+    }
+
+    /// Convert the field with the name. Panics, if there is no such field.
+    pub fn named<T: ScanField>(&self, name: &str) -> Result<T, ScanError> {
+        let index = self
+            .fields
+            .iter()
+            .position(|field| field.name == Some(name))
+            .unwrap_or_else(|| panic!("There is no field {{{}}}.", name));
+        self.get(index)
+    }
+
+    /// Convert all fields into a tuple.
+    pub fn parse<T: FromFields>(&self) -> Result<T, ScanError> {
+        T::from_fields(self)
+    }
+}
+
+/// Match the input against the template, see [`Template`].
+pub fn scan<'a>(template: &'a str, input: &'a str) -> Result<Scanned<'a>, ScanError> {
+    Template::new(template).scan(input)
+}
+
+/// Match the template repeatedly, e.g. once per line, and convert every record into a tuple.
+pub fn scan_all<T: FromFields>(template: &str, input: &str) -> Result<Vec<T>, ScanError> {
+    Template::new(template).scan_all(input)
+}
+
+/// Scan the input and convert all fields into a tuple, the types come from the binding:
+/// `let (button, x, y): (char, i64, i64) = scan!("Button {}: X+{}, Y+{}", line)?;`
+#[macro_export]
+macro_rules! scan {
+    ($template:expr, $input:expr) => {
+        $crate::scan::scan($template, $input).and_then(|scanned| scanned.parse())
+    };
+}
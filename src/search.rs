@@ -0,0 +1,463 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+use crate::{geometry::Point, graph::Graph, grid::BitGrid};
+
+#[test]
+pub fn test_bfs_and_dfs() {
+    let grid = crate::grid::Grid::parse("..#.\n..#.\n....", |ch| ch);
+    let neighbours = |point: &Point| {
+        grid.neighbours_4(*point)
+            .filter(|neighbour| grid[*neighbour] != '#')
+            .collect::<Vec<Point>>()
+    };
+    let goal = Point::new(3, 0);
+
+    let result = Search::new()
+        .with_paths()
+        .bfs([Point::new(0, 0)], neighbours, |point, _| {
+            Control::stop_if(*point == goal)
+        });
+    assert_eq!(result.goal, Some(goal));
+    assert_eq!(result.cost, Some(7));
+    let path = result.path().unwrap();
+    assert_eq!(path.len(), 8);
+    assert_eq!(path.first(), Some(&Point::new(0, 0)));
+    assert_eq!(path.last(), Some(&goal));
+
+    // bit grids work as visited set for points
+    let mut count = 0;
+    let result = Search::new()
+        .visited(BitGrid::new(grid.width(), grid.height()))
+        .dfs([Point::new(0, 0)], neighbours, |point, _| {
+            count += 1;
+            Control::Continue
+        });
+    assert_eq!(result.goal, None);
+    assert_eq!(count, 10);
+
+    let graph = Search::new().explore([Point::new(0, 0)], neighbours);
+    assert_eq!(graph.len(), 10);
+    // every open field is connected in both directions
+    assert_eq!(graph.edge_count(), 2 * 11);
+}
+
+#[test]
+pub fn test_dijkstra_and_astar() {
+    // entering a field costs its digit
+    let grid = crate::grid::Grid::parse("1911\n1919\n1111", |ch| ch.to_digit(10).unwrap() as u64);
+    let neighbours = |point: &Point| {
+        grid.neighbours_4(*point)
+            .map(|neighbour| (neighbour, grid[neighbour]))
+            .collect::<Vec<(Point, u64)>>()
+    };
+    let start = Point::new(0, 0);
+    let goal = Point::new(3, 0);
+
+    let result = Search::new()
+        .with_paths()
+        .dijkstra([start], neighbours, |point, _| {
+            Control::stop_if(*point == goal)
+        });
+    assert_eq!(result.cost, Some(7));
+    assert_eq!(result.path().unwrap().len(), 8);
+
+    let result = Search::new().with_paths().astar(
+        [start],
+        neighbours,
+        |point| point.manhattan_distance(&goal),
+        |point, _| Control::stop_if(*point == goal),
+    );
+    assert_eq!(result.cost, Some(7));
+    assert_eq!(result.path().unwrap().len(), 8);
+}
+
+/// What a search should do with a state, that is visited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    /// Expand the neighbours of the state.
+    Continue,
+    /// Do not expand the neighbours of the state, but keep searching.
+    Prune,
+    /// Stop the search, the state is the goal.
+    Stop,
+}
+impl Control {
+    pub fn stop_if(condition: bool) -> Control {
+        if condition {
+            return Control::Stop;
+        }
+        Control::Continue
+    }
+}
+
+/// Decides, if a state is visited for the first time. Only new states are expanded.
+pub trait VisitedSet<S> {
+    /// Mark the state as visited. Returns true, if it was not visited before.
+    fn insert(&mut self, state: &S) -> bool;
+}
+
+impl<S: Hash + Eq + Clone> VisitedSet<S> for HashSet<S> {
+    fn insert(&mut self, state: &S) -> bool {
+        HashSet::insert(self, state.clone())
+    }
+}
+
+impl VisitedSet<Point> for BitGrid {
+    fn insert(&mut self, state: &Point) -> bool {
+        self.set(*state)
+    }
+}
+
+/// Never remembers anything, so every state is expanded each time it is reached.
+/// Turns the graph search into a tree search, e.g. to visit every path of an acyclic graph.
+/// Never use it on graphs with cycles, the search would not end.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoVisited;
+
+impl<S> VisitedSet<S> for NoVisited {
+    fn insert(&mut self, state: &S) -> bool {
+        true
+    }
+}
+
+/// Outcome of a search.
+#[derive(Debug, Clone)]
+pub struct SearchResult<S> {
+    /// The state, for which the visitor returned Stop.
+    pub goal: Option<S>,
+    /// Number of steps (bfs, dfs) or summed up costs (dijkstra, astar) to the goal.
+    pub cost: Option<u64>,
+    /// Number of states, that have been visited.
+    pub visited_count: usize,
+    path: Option<Vec<S>>,
+}
+impl<S> SearchResult<S> {
+    /// All states from the start to the goal. Only available, if paths are tracked and the goal was found.
+    pub fn path(&self) -> Option<&[S]> {
+        self.path.as_deref()
+    }
+
+    pub fn into_path(self) -> Option<Vec<S>> {
+        self.path
+    }
+}
+
+/// Configuration of a search over states of type S, generic over the way visited states are remembered.
+/// The neighbour function and the visitor are given to the actual search method.
+///
+/// The visitor is called once for each state, that is visited, together with its depth or cost.
+/// Its result decides, if the search continues, skips the neighbours of the state, or stops early.
+#[derive(Debug, Clone)]
+pub struct Search<S, V = HashSet<S>> {
+    visited: V,
+    track_paths: bool,
+    _state: std::marker::PhantomData<S>,
+}
+
+impl<S: Hash + Eq + Clone> Search<S> {
+    /// Search with a hash set of visited states.
+    pub fn new() -> Self {
+        Search {
+            visited: HashSet::new(),
+            track_paths: false,
+            _state: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S: Hash + Eq + Clone> Default for Search<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Clone, V: VisitedSet<S>> Search<S, V> {
+    /// Use a different strategy to remember visited states, e.g. a BitGrid or NoVisited.
+    pub fn visited<W: VisitedSet<S>>(self, visited: W) -> Search<S, W> {
+        Search {
+            visited,
+            track_paths: self.track_paths,
+            _state: std::marker::PhantomData,
+        }
+    }
+
+    /// Remember the parent of every visited state, to be able to return the path to the goal.
+    pub fn with_paths(mut self) -> Self {
+        self.track_paths = true;
+        self
+    }
+
+    /// Breadth first search. The cost passed to the visitor is the number of steps from the nearest start.
+    pub fn bfs<I: IntoIterator<Item = S>>(
+        mut self,
+        starts: impl IntoIterator<Item = S>,
+        mut neighbours: impl FnMut(&S) -> I,
+        mut visit: impl FnMut(&S, u64) -> Control,
+    ) -> SearchResult<S> {
+        let mut parents = Parents::new(self.track_paths);
+        let mut queue: VecDeque<(S, u64, Option<usize>)> = VecDeque::new();
+
+        // mark states as visited when they are queued, a later path to them can never be shorter
+        starts.into_iter().for_each(|start| {
+            if self.visited.insert(&start) {
+                queue.push_back((start, 0, None));
+            }
+        });
+
+        let mut visited_count = 0;
+        while let Some((state, depth, parent)) = queue.pop_front() {
+            visited_count += 1;
+            let node = parents.push(&state, parent);
+            match visit(&state, depth) {
+                Control::Stop => return parents.found(state, depth, visited_count, node),
+                Control::Prune => continue,
+                Control::Continue => (),
+            }
+            neighbours(&state).into_iter().for_each(|neighbour| {
+                if self.visited.insert(&neighbour) {
+                    queue.push_back((neighbour, depth + 1, node));
+                }
+            });
+        }
+        SearchResult::not_found(visited_count)
+    }
+
+    /// Iterative depth first search. The cost passed to the visitor is the depth of the state on the current path.
+    pub fn dfs<I: IntoIterator<Item = S>>(
+        mut self,
+        starts: impl IntoIterator<Item = S>,
+        mut neighbours: impl FnMut(&S) -> I,
+        mut visit: impl FnMut(&S, u64) -> Control,
+    ) -> SearchResult<S> {
+        let mut parents = Parents::new(self.track_paths);
+        let mut stack: Vec<(S, u64, Option<usize>)> =
+            starts.into_iter().map(|start| (start, 0, None)).collect();
+        // keep the given order of the starts
+        stack.reverse();
+
+        let mut visited_count = 0;
+        while let Some((state, depth, parent)) = stack.pop() {
+            // mark states as visited when they are taken from the stack, to keep the depth first order
+            if !self.visited.insert(&state) {
+                continue;
+            }
+            visited_count += 1;
+            let node = parents.push(&state, parent);
+            match visit(&state, depth) {
+                Control::Stop => return parents.found(state, depth, visited_count, node),
+                Control::Prune => continue,
+                Control::Continue => (),
+            }
+            let index = stack.len();
+            stack.extend(
+                neighbours(&state)
+                    .into_iter()
+                    .map(|neighbour| (neighbour, depth + 1, node)),
+            );
+            // first neighbour is visited first
+            stack[index..].reverse();
+        }
+        SearchResult::not_found(visited_count)
+    }
+
+    /// Cheapest paths for non-negative costs. The neighbour function returns each neighbour with the cost to move there.
+    /// States are visited in order of their total cost, which is passed to the visitor.
+    pub fn dijkstra<I: IntoIterator<Item = (S, u64)>>(
+        self,
+        starts: impl IntoIterator<Item = S>,
+        neighbours: impl FnMut(&S) -> I,
+        visit: impl FnMut(&S, u64) -> Control,
+    ) -> SearchResult<S> {
+        self.astar(starts, neighbours, |_| 0, visit)
+    }
+
+    /// Dijkstra, guided by a heuristic of the remaining cost to the goal.
+    /// The heuristic must never overestimate, otherwise the found path might not be the cheapest.
+    pub fn astar<I: IntoIterator<Item = (S, u64)>>(
+        mut self,
+        starts: impl IntoIterator<Item = S>,
+        mut neighbours: impl FnMut(&S) -> I,
+        mut heuristic: impl FnMut(&S) -> u64,
+        mut visit: impl FnMut(&S, u64) -> Control,
+    ) -> SearchResult<S> {
+        let mut parents = Parents::new(self.track_paths);
+        let mut queue: BinaryHeap<Candidate<S>> = BinaryHeap::new();
+        // insertion order breaks ties, so equally good candidates are visited first in, first out
+        let mut sequence = 0;
+
+        starts.into_iter().for_each(|start| {
+            queue.push(Candidate {
+                priority: heuristic(&start),
+                sequence,
+                cost: 0,
+                state: start,
+                parent: None,
+            });
+            sequence += 1;
+        });
+
+        let mut visited_count = 0;
+        while let Some(candidate) = queue.pop() {
+            // a state can be queued multiple times, only the cheapest one counts
+            if !self.visited.insert(&candidate.state) {
+                continue;
+            }
+            visited_count += 1;
+            let node = parents.push(&candidate.state, candidate.parent);
+            match visit(&candidate.state, candidate.cost) {
+                Control::Stop => {
+                    return parents.found(candidate.state, candidate.cost, visited_count, node)
+                }
+                Control::Prune => continue,
+                Control::Continue => (),
+            }
+            neighbours(&candidate.state)
+                .into_iter()
+                .for_each(|(neighbour, step_cost)| {
+                    let cost = candidate.cost + step_cost;
+                    queue.push(Candidate {
+                        priority: cost + heuristic(&neighbour),
+                        sequence,
+                        cost,
+                        state: neighbour,
+                        parent: node,
+                    });
+                    sequence += 1;
+                });
+        }
+        SearchResult::not_found(visited_count)
+    }
+
+    /// Build the graph of every state reachable from the starts, with all edges between them, e.g. to export it with [`crate::dot::Dot`].
+    /// Every edge has weight 1.
+    pub fn explore<I: IntoIterator<Item = S>>(
+        self,
+        starts: impl IntoIterator<Item = S>,
+        mut neighbours: impl FnMut(&S) -> I,
+    ) -> Graph<S>
+    where
+        S: Hash + Eq,
+    {
+        self.explore_weighted(starts, |state| {
+            neighbours(state)
+                .into_iter()
+                .map(|neighbour| (neighbour, 1))
+        })
+    }
+
+    /// Like [`Search::explore`], with the costs of the neighbour function as edge weights.
+    pub fn explore_weighted<I: IntoIterator<Item = (S, u64)>>(
+        self,
+        starts: impl IntoIterator<Item = S>,
+        mut neighbours: impl FnMut(&S) -> I,
+    ) -> Graph<S>
+    where
+        S: Hash + Eq,
+    {
+        let mut graph = Graph::new();
+        // the neighbour function is called exactly once for every reachable state, so it records the edges
+        self.bfs(
+            starts,
+            |state| {
+                let from = graph.add_node(state.clone());
+                neighbours(state)
+                    .into_iter()
+                    .map(|(neighbour, weight)| {
+                        let to = graph.add_node(neighbour.clone());
+                        graph.add_edge_by_index(from, to, weight);
+                        neighbour
+                    })
+                    .collect::<Vec<S>>()
+            },
+            |_, _| Control::Continue,
+        );
+        graph
+    }
+}
+
+impl<S> SearchResult<S> {
+    fn not_found(visited_count: usize) -> Self {
+        SearchResult {
+            goal: None,
+            cost: None,
+            visited_count,
+            path: None,
+        }
+    }
+}
+
+/// Entry of the priority queue. Ordered by priority, lowest first, because BinaryHeap is a max heap.
+/// Equal priorities are ordered by the insertion sequence, so the search and its results are deterministic.
+struct Candidate<S> {
+    priority: u64,
+    sequence: u64,
+    cost: u64,
+    state: S,
+    parent: Option<usize>,
+}
+impl<S> PartialEq for Candidate<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl<S> Eq for Candidate<S> {}
+impl<S> PartialOrd for Candidate<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<S> Ord for Candidate<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.priority, other.sequence).cmp(&(self.priority, self.sequence))
+    }
+}
+
+/// Every visited state with the index of its parent, if paths are tracked.
+/// Storing nodes instead of a map from state to parent also works for tree searches, where states repeat.
+struct Parents<S> {
+    nodes: Option<Vec<(S, Option<usize>)>>,
+}
+impl<S: Clone> Parents<S> {
+    fn new(track_paths: bool) -> Self {
+        Self {
+            nodes: track_paths.then(Vec::new),
+        }
+    }
+
+    /// Returns the index of the new node, if paths are tracked.
+    fn push(&mut self, state: &S, parent: Option<usize>) -> Option<usize> {
+        let nodes = self.nodes.as_mut()?;
+        nodes.push((state.clone(), parent));
+        Some(nodes.len() - 1)
+    }
+
+    fn found(
+        self,
+        goal: S,
+        cost: u64,
+        visited_count: usize,
+        node: Option<usize>,
+    ) -> SearchResult<S> {
+        let path = self.nodes.map(|nodes| {
+            let mut path = Vec::new();
+            let mut node = node;
+            while let Some(index) = node {
+                let (state, parent) = &nodes[index];
+                path.push(state.clone());
+                node = *parent;
+            }
+            path.reverse();
+            path
+        });
+        SearchResult {
+            goal: Some(goal),
+            cost: Some(cost),
+            visited_count,
+            path,
+        }
+    }
+}